@@ -0,0 +1,684 @@
+use std::borrow::Cow;
+use std::collections::TryReserveError;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::mem;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use validate::Rule;
+
+pub mod defaults;
+pub mod env;
+pub mod intern;
+#[cfg(feature = "toml")]
+pub mod layered;
+pub mod profile;
+pub mod reload;
+pub mod validate;
+
+/// The same `Config` used throughout the examples: a small `path` plus
+/// a potentially very large vector that makes cloning expensive.
+///
+/// `Config::default()` has an empty `path` and an empty vector, so it is
+/// not valid on its own.
+#[derive(Clone, Debug, Default, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config {
+    pub path: String,
+    pub very_long_vector: Vec<String>,
+}
+
+/// A compact, log-friendly form that summarizes the vector by length.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Config {{ path: {:?}, items: {} }}",
+            self.path,
+            self.very_long_vector.len()
+        )
+    }
+}
+
+/// Two configs are equal when their `path`s match and their vectors hold
+/// the same items in the same order. How much capacity either vector has
+/// reserved is irrelevant, so configs built with different
+/// `Vec::with_capacity` sizes but identical data compare equal.
+impl PartialEq for Config {
+    fn eq(&self, other: &Config) -> bool {
+        self.path == other.path && self.very_long_vector[..] == other.very_long_vector[..]
+    }
+}
+
+/// Hashes the same data that [`PartialEq`] compares, so reserved
+/// capacity never affects the hash either.
+impl Hash for Config {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.path.hash(state);
+        self.very_long_vector[..].hash(state);
+    }
+}
+
+/// A terse alternative to [`ConfigBuilder`] for a config that is only a
+/// path. The string is copied into a new `String`; an empty one is
+/// rejected with [`ConfigError::EmptyPath`].
+impl TryFrom<&str> for Config {
+    type Error = ConfigError;
+
+    fn try_from(path: &str) -> Result<Config, ConfigError> {
+        if path.is_empty() {
+            return Err(ConfigError::EmptyPath);
+        }
+
+        Ok(Config {
+            path: String::from(path),
+            very_long_vector: Vec::new(),
+        })
+    }
+}
+
+impl Config {
+    /// The baseline config for each deployment environment.
+    pub fn for_environment(env: Environment) -> Config {
+        let path = match env {
+            Environment::Dev => "/etc/nginx/nginx.dev.conf",
+            Environment::Staging => "/etc/nginx/nginx.staging.conf",
+            Environment::Prod => "/etc/nginx/nginx.conf",
+        };
+
+        Config {
+            path: String::from(path),
+            very_long_vector: Vec::new(),
+        }
+    }
+
+    /// Like [`is_valid_config`], validating only needs to borrow the
+    /// config, but reports *why* it is invalid. It stops at the first
+    /// failing rule, without building a [`Validator`](validate::Validator)
+    /// or allocating; see [`Config::validate_all`] for every failure.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        // The same rules, in the same order, as `Validator::standard`.
+        validate::NonEmptyPath.check(self)?;
+        validate::AbsolutePath.check(self)
+    }
+
+    /// Checks every rule and collects all of the failures, rather than
+    /// stopping at the first. The rules are those of
+    /// [`Validator::standard`](validate::Validator::standard).
+    pub fn validate_all(&self) -> Result<(), Vec<ConfigError>> {
+        validate::Validator::standard().check(self)
+    }
+
+    /// A safe alternative to `Vec::with_capacity` for large capacities
+    /// like the `CAPACITY` constant in the examples: a failed allocation
+    /// is reported as [`ConfigError::Alloc`] rather than aborting.
+    pub fn with_reserved(path: impl Into<String>, capacity: usize) -> Result<Config, ConfigError> {
+        let mut very_long_vector = Vec::new();
+        very_long_vector
+            .try_reserve(capacity)
+            .map_err(ConfigError::Alloc)?;
+
+        Ok(Config {
+            path: path.into(),
+            very_long_vector,
+        })
+    }
+
+    /// A config whose vector actually holds `items`, rather than only
+    /// reserving room for them. `items` is moved in without copying.
+    pub fn with_items(path: impl Into<String>, items: Vec<String>) -> Config {
+        Config {
+            path: path.into(),
+            very_long_vector: items,
+        }
+    }
+
+    /// Borrows the entries of `very_long_vector`.
+    pub fn items(&self) -> &[String] {
+        &self.very_long_vector
+    }
+
+    /// Replaces `path` through a mutable borrow, refusing an empty path
+    /// the same way [`Config::validate`] does. On error the old path is
+    /// left in place.
+    pub fn set_path(&mut self, path: impl Into<String>) -> Result<(), ConfigError> {
+        let path = path.into();
+        if path.is_empty() {
+            return Err(ConfigError::EmptyPath);
+        }
+
+        self.path = path;
+        Ok(())
+    }
+
+    /// Consumes the config, lets `f` mutate it, then re-validates. A
+    /// config that no longer validates is handed back alongside the error
+    /// rather than dropped, with `f`'s changes still applied.
+    pub fn apply(mut self, f: impl FnOnce(&mut Config)) -> Result<Config, (Config, ConfigError)> {
+        f(&mut self);
+        match self.validate() {
+            Ok(()) => Ok(self),
+            Err(err) => Err((self, err)),
+        }
+    }
+
+    /// Runs [`Config::validate`], then additionally checks that `path`
+    /// names an existing file on disk.
+    pub fn validate_exists(&self) -> Result<(), ConfigError> {
+        self.validate()?;
+
+        let metadata = fs::metadata(&self.path).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => ConfigError::NotFound(self.path.clone()),
+            _ => ConfigError::UnreadableFile {
+                path: self.path.clone(),
+                source: err,
+            },
+        })?;
+
+        if !metadata.is_file() {
+            return Err(ConfigError::NotAFile(self.path.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Resolves `..`, `.`, redundant separators and symlinks in `path`,
+    /// storing the absolute result back into the config it consumed.
+    ///
+    /// `path` is a `String`, so a result that is not valid Unicode, such as
+    /// a symlink to a file with a non-UTF-8 name, is an error rather than
+    /// being stored lossily.
+    pub fn canonicalize_path(mut self) -> Result<Config, ConfigError> {
+        let canonical = fs::canonicalize(&self.path).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => ConfigError::NotFound(self.path.clone()),
+            _ => ConfigError::UnreadableFile {
+                path: self.path.clone(),
+                source: err,
+            },
+        })?;
+
+        self.path = canonical
+            .into_os_string()
+            .into_string()
+            .map_err(|canonical| {
+                ConfigError::NonUnicodePath(canonical.to_string_lossy().into_owned())
+            })?;
+        Ok(self)
+    }
+
+    /// The `/`-separated segments of `path`, with empty ones dropped. The
+    /// segments borrow from `path`, so nothing is copied.
+    pub fn path_components(&self) -> Vec<&str> {
+        self.path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect()
+    }
+
+    /// Whether `path` matches a shell-style glob such as
+    /// `/etc/nginx/*.conf`. Only borrows the config; an invalid pattern
+    /// matches nothing.
+    #[cfg(feature = "glob")]
+    pub fn matches_glob(&self, pattern: &str) -> bool {
+        glob::Pattern::new(pattern).is_ok_and(|pattern| pattern.matches(&self.path))
+    }
+
+    /// Tidies `path` as a string, without touching the filesystem:
+    /// surrounding whitespace is trimmed, runs of `/` are collapsed and a
+    /// trailing `/` is dropped unless the path is just `/`. Unlike
+    /// [`Config::canonicalize_path`], `..` and symlinks are left alone.
+    pub fn sanitize_path(mut self) -> Config {
+        let trimmed = self.path.trim();
+        let mut sanitized = String::with_capacity(trimmed.len());
+        for c in trimmed.chars() {
+            if !(c == '/' && sanitized.ends_with('/')) {
+                sanitized.push(c);
+            }
+        }
+        if sanitized.len() > 1 && sanitized.ends_with('/') {
+            sanitized.pop();
+        }
+
+        self.path = sanitized;
+        self
+    }
+
+    /// [`Config::expand_env`] followed by [`Config::canonicalize_path`].
+    /// Variables have to be expanded first, since `$HOME/nginx.conf` means
+    /// nothing to the filesystem.
+    pub fn normalize(self) -> Result<Config, ConfigError> {
+        self.expand_env().canonicalize_path()
+    }
+
+    /// Moves the config into a new `Arc`, the spelled-out form of the
+    /// `let config = Arc::new(config);` rebinding in `example_5`. See
+    /// [`WorkerPool::from_arc`](crate::worker::WorkerPool::from_arc).
+    pub fn into_arc(self) -> Arc<Config> {
+        Arc::new(self)
+    }
+
+    /// See [`ConfigBuilder::from`].
+    pub fn into_builder(self) -> ConfigBuilder {
+        ConfigBuilder::from(self)
+    }
+
+    /// Overlays `other` onto `self`. Both configs are moved in, so the
+    /// winning `path` and vector are moved into the result rather than
+    /// copied. A non-empty `other.path` wins, as does the vector with the
+    /// larger capacity.
+    pub fn merge(self, other: Config) -> Config {
+        let path = if other.path.is_empty() {
+            self.path
+        } else {
+            other.path
+        };
+
+        let very_long_vector =
+            if other.very_long_vector.capacity() > self.very_long_vector.capacity() {
+                other.very_long_vector
+            } else {
+                self.very_long_vector
+            };
+
+        Config {
+            path,
+            very_long_vector,
+        }
+    }
+
+    /// Substitutes `$VAR` and `${VAR}` tokens in `path` with the values
+    /// from the environment. Unknown variables are left untouched.
+    ///
+    /// Like `merge`, this consumes the config and returns it, so no copy
+    /// of the (potentially large) vector is ever made.
+    pub fn expand_env(mut self) -> Config {
+        self.path = expand_env_vars(&self.path);
+        self
+    }
+
+    /// An estimate of how many heap bytes a `clone` would copy: the
+    /// `path`, plus each item in the vector and the `String` header that
+    /// holds it. Spare capacity is not copied by `clone`, so it is not
+    /// counted. Useful for deciding between cloning and sharing an `Arc`.
+    pub fn clone_cost(&self) -> usize {
+        let items: usize = self.very_long_vector.iter().map(String::len).sum();
+        self.path.len() + self.very_long_vector.len() * mem::size_of::<String>() + items
+    }
+
+    /// A fingerprint of `path` and every item, for skipping reloads that
+    /// would not change anything. It hashes the same data as the `Hash`
+    /// impl, so it is not affected by reserved capacity. `DefaultHasher::new`
+    /// is deterministic within a build, but checksums should not be
+    /// persisted across Rust releases.
+    pub fn checksum(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Reads a TOML file containing a `path` string. The file contents
+    /// are only borrowed while parsing; the returned `Config` owns a
+    /// fresh copy of the `path` value.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_path(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        Config::from_file(path.as_ref(), Format::Toml)
+    }
+
+    /// The YAML counterpart of [`Config::from_toml_path`], reading a
+    /// `path:` key.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_path(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        Config::from_file(path.as_ref(), Format::Yaml)
+    }
+
+    /// Reads a document in `format` with a `path` key from any reader:
+    /// stdin, a socket or an in-memory `&[u8]`. The reader is consumed,
+    /// and a failed read is reported as [`ConfigError::Io`].
+    #[cfg(any(feature = "toml", feature = "yaml", feature = "serde"))]
+    pub fn from_reader<R: io::Read>(mut reader: R, format: Format) -> Result<Config, ConfigError> {
+        let mut contents = String::new();
+        reader
+            .read_to_string(&mut contents)
+            .map_err(ConfigError::Io)?;
+
+        let config_path = match format {
+            #[cfg(feature = "toml")]
+            Format::Toml => {
+                let table: toml::Table = contents
+                    .parse()
+                    .map_err(|err| ConfigError::InvalidToml(Box::new(err)))?;
+                table
+                    .get("path")
+                    .and_then(toml::Value::as_str)
+                    .map(String::from)
+            }
+            #[cfg(feature = "yaml")]
+            Format::Yaml => {
+                let value: serde_yaml::Value =
+                    serde_yaml::from_str(&contents).map_err(ConfigError::InvalidYaml)?;
+                value
+                    .get("path")
+                    .and_then(serde_yaml::Value::as_str)
+                    .map(String::from)
+            }
+            #[cfg(feature = "serde")]
+            Format::Json => {
+                let value: serde_json::Value =
+                    serde_json::from_str(&contents).map_err(ConfigError::InvalidJson)?;
+                value
+                    .get("path")
+                    .and_then(serde_json::Value::as_str)
+                    .map(String::from)
+            }
+        };
+
+        Ok(Config {
+            path: config_path.ok_or(ConfigError::MissingPath)?,
+            very_long_vector: Vec::new(),
+        })
+    }
+
+    /// Opens `path` and hands it to [`Config::from_reader`], attaching the
+    /// path to any IO error.
+    #[cfg(any(feature = "toml", feature = "yaml"))]
+    fn from_file(path: &Path, format: Format) -> Result<Config, ConfigError> {
+        let unreadable = |source: io::Error| match source.kind() {
+            io::ErrorKind::NotFound => ConfigError::MissingFile {
+                path: path.display().to_string(),
+                source,
+            },
+            _ => ConfigError::UnreadableFile {
+                path: path.display().to_string(),
+                source,
+            },
+        };
+
+        let file = fs::File::open(path).map_err(unreadable)?;
+        Config::from_reader(file, format).map_err(|err| match err {
+            ConfigError::Io(source) => unreadable(source),
+            err => err,
+        })
+    }
+}
+
+/// The document formats [`Config::from_reader`] understands. Each is only
+/// available with its feature: `toml`, `yaml` or `serde` for JSON.
+#[cfg(any(feature = "toml", feature = "yaml", feature = "serde"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    #[cfg(feature = "toml")]
+    Toml,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Environment {
+    Dev,
+    Staging,
+    Prod,
+}
+
+/// Parses `"dev"`, `"staging"` or `"prod"`, ignoring case.
+impl FromStr for Environment {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Environment, ConfigError> {
+        match s.to_ascii_lowercase().as_str() {
+            "dev" => Ok(Environment::Dev),
+            "staging" => Ok(Environment::Staging),
+            "prod" => Ok(Environment::Prod),
+            _ => Err(ConfigError::UnknownEnvironment(String::from(s))),
+        }
+    }
+}
+
+/// Builds a [`Config`] without spelling out `String::from` and
+/// `Vec::with_capacity` at every call site. Each method takes the
+/// builder by value and hands it back, so calls can be chained. A builder
+/// with nothing set builds `Config::default()`.
+#[derive(Debug, Default)]
+pub struct ConfigBuilder {
+    path: String,
+    very_long_vector: Vec<String>,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        ConfigBuilder::default()
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Pre-allocates `very_long_vector` with room for `capacity` items.
+    pub fn reserve(mut self, capacity: usize) -> Self {
+        let additional = capacity.saturating_sub(self.very_long_vector.len());
+        self.very_long_vector.reserve_exact(additional);
+        self
+    }
+
+    pub fn build(self) -> Config {
+        Config {
+            path: self.path,
+            very_long_vector: self.very_long_vector,
+        }
+    }
+}
+
+/// Seeds a builder with an existing config's fields, so one value can be
+/// overridden without respecifying the rest. The vector is moved into the
+/// builder, keeping both its contents and its reserved capacity.
+impl From<Config> for ConfigBuilder {
+    fn from(config: Config) -> Self {
+        ConfigBuilder {
+            path: config.path,
+            very_long_vector: config.very_long_vector,
+        }
+    }
+}
+
+fn expand_env_vars(input: &str) -> String {
+    let mut expanded = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        // `name_start..name_end` is the variable name within `after`, and
+        // `token_end` is where the whole token ends, closing brace included.
+        let (name_start, name_end, token_end) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (1, end + 1, end + 2),
+                None => (0, 0, 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (0, end, end)
+            }
+        };
+
+        let name = &after[name_start..name_end];
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => expanded.push_str(&value),
+            _ => expanded.push_str(&rest[start..start + 1 + token_end]),
+        }
+
+        rest = &after[token_end..];
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Checking to see if [`Config.path`] is valid does not
+/// require a dedicated copy or exclusive control of the value
+/// so borrowing is the best choice here.
+pub fn is_valid_config(config: &Config) -> bool {
+    config.validate().is_ok()
+}
+
+/// The same check as [`is_valid_config`], for callers that may hold
+/// either a borrowed or an owned config. Neither is cloned: a borrow is
+/// only read through, and an owned config is dropped afterwards.
+pub fn is_valid_config_cow<'a>(config: impl Into<Cow<'a, Config>>) -> bool {
+    is_valid_config(&config.into())
+}
+
+impl<'a> From<&'a Config> for Cow<'a, Config> {
+    fn from(config: &'a Config) -> Self {
+        Cow::Borrowed(config)
+    }
+}
+
+impl From<Config> for Cow<'_, Config> {
+    fn from(config: Config) -> Self {
+        Cow::Owned(config)
+    }
+}
+
+/// Variants caused by an underlying IO, parse or allocation failure carry
+/// that error and return it from [`Error::source`], so callers can walk
+/// the whole chain rather than just the top-level message.
+#[derive(Debug)]
+pub enum ConfigError {
+    EmptyPath,
+    NonAbsolutePath(String),
+    MissingFile {
+        path: String,
+        source: io::Error,
+    },
+    UnreadableFile {
+        path: String,
+        source: io::Error,
+    },
+    /// Boxed because `toml::de::Error` is several times larger than any
+    /// other variant.
+    #[cfg(feature = "toml")]
+    InvalidToml(Box<toml::de::Error>),
+    #[cfg(feature = "yaml")]
+    InvalidYaml(serde_yaml::Error),
+    MissingPath,
+    NotFound(String),
+    NotAFile(String),
+    /// A resolved path is not valid Unicode; shown lossily.
+    NonUnicodePath(String),
+    UnknownEnvironment(String),
+    Alloc(TryReserveError),
+    MissingEnvVar(String),
+    /// The named environment variable is set, but not to valid Unicode.
+    NonUnicodeEnvVar(String),
+    InvalidCapacity(String),
+    UnwritableFile {
+        path: String,
+        source: io::Error,
+    },
+    #[cfg(feature = "serde")]
+    InvalidJson(serde_json::Error),
+    /// Raised by a user-defined [`validate::Rule`].
+    RuleFailed(String),
+    /// A read from something other than a named file failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::EmptyPath => write!(f, "config path is empty"),
+            ConfigError::NonAbsolutePath(path) => {
+                write!(f, "config path `{path}` is not absolute")
+            }
+            ConfigError::MissingFile { path, .. } => {
+                write!(f, "config file `{path}` does not exist")
+            }
+            ConfigError::UnreadableFile { path, .. } => {
+                write!(f, "config file `{path}` is unreadable")
+            }
+            #[cfg(feature = "toml")]
+            ConfigError::InvalidToml(reason) => {
+                write!(f, "config file is not valid TOML: {reason}")
+            }
+            #[cfg(feature = "yaml")]
+            ConfigError::InvalidYaml(reason) => {
+                write!(f, "config file is not valid YAML: {reason}")
+            }
+            ConfigError::MissingPath => write!(f, "config file has no `path` key"),
+            ConfigError::NotFound(path) => write!(f, "config path `{path}` does not exist"),
+            ConfigError::NotAFile(path) => write!(f, "config path `{path}` is not a file"),
+            ConfigError::NonUnicodePath(path) => {
+                write!(f, "config path `{path}` is not valid Unicode")
+            }
+            ConfigError::UnknownEnvironment(env) => write!(f, "unknown environment `{env}`"),
+            ConfigError::Alloc(err) => write!(f, "could not allocate config: {err}"),
+            ConfigError::MissingEnvVar(var) => write!(f, "environment variable `{var}` is not set"),
+            ConfigError::NonUnicodeEnvVar(var) => {
+                write!(f, "environment variable `{var}` is not valid Unicode")
+            }
+            ConfigError::InvalidCapacity(value) => {
+                write!(f, "`{value}` is not a valid capacity")
+            }
+            ConfigError::UnwritableFile { path, .. } => {
+                write!(f, "config file `{path}` could not be written")
+            }
+            #[cfg(feature = "serde")]
+            ConfigError::InvalidJson(reason) => {
+                write!(f, "config file is not valid JSON: {reason}")
+            }
+            ConfigError::RuleFailed(reason) => write!(f, "{reason}"),
+            ConfigError::Io(err) => write!(f, "could not read config: {err}"),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::MissingFile { source, .. } => Some(source),
+            ConfigError::UnreadableFile { source, .. } => Some(source),
+            #[cfg(feature = "toml")]
+            ConfigError::InvalidToml(err) => Some(err.as_ref()),
+            #[cfg(feature = "yaml")]
+            ConfigError::InvalidYaml(err) => Some(err),
+            ConfigError::Alloc(err) => Some(err),
+            ConfigError::UnwritableFile { source, .. } => Some(source),
+            #[cfg(feature = "serde")]
+            ConfigError::InvalidJson(err) => Some(err),
+            ConfigError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Lets `?` lift a `ConfigError` into a function returning `io::Result`.
+///
+/// Errors that came from the filesystem keep their original
+/// [`io::ErrorKind`]; everything else is reported as
+/// [`io::ErrorKind::InvalidData`]. The `ConfigError` itself is kept as the
+/// payload, so its message and source are not lost.
+impl From<ConfigError> for io::Error {
+    fn from(err: ConfigError) -> io::Error {
+        let kind = match err {
+            ConfigError::Io(err) => return err,
+            ConfigError::MissingFile { ref source, .. }
+            | ConfigError::UnreadableFile { ref source, .. }
+            | ConfigError::UnwritableFile { ref source, .. } => source.kind(),
+            ConfigError::NotFound(_) => io::ErrorKind::NotFound,
+            ConfigError::Alloc(_) => io::ErrorKind::OutOfMemory,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, err)
+    }
+}
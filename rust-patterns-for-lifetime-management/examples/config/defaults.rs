@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+use super::Config;
+
+/// Named baseline configs shared by the whole process. The map is created
+/// on first use and owns every config registered into it.
+fn registry() -> &'static RwLock<HashMap<String, Config>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Config>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Moves `config` into the registry under `name`, replacing (and
+/// dropping) any config already registered with that name.
+pub fn register(name: &str, config: Config) {
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(String::from(name), config);
+}
+
+/// Returns a clone of the config registered as `name`. The registry keeps
+/// its own copy, so callers are free to modify theirs.
+pub fn get(name: &str) -> Option<Config> {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .cloned()
+}
@@ -0,0 +1,33 @@
+use std::env::{self, VarError};
+
+use super::{Config, ConfigError};
+
+/// Builds a config entirely from the environment, twelve-factor style.
+///
+/// `{prefix}_PATH` is required. `{prefix}_CAPACITY` is optional and, when
+/// set, is parsed as the number of items to reserve in the vector.
+///
+/// A variable that is set but not valid Unicode is an error, never
+/// treated as unset.
+pub fn from_prefix(prefix: &str) -> Result<Config, ConfigError> {
+    let path_var = format!("{prefix}_PATH");
+    let path = match env::var(&path_var) {
+        Ok(path) => path,
+        Err(VarError::NotPresent) => return Err(ConfigError::MissingEnvVar(path_var)),
+        Err(VarError::NotUnicode(_)) => return Err(ConfigError::NonUnicodeEnvVar(path_var)),
+    };
+
+    let capacity = match env::var(format!("{prefix}_CAPACITY")) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| ConfigError::InvalidCapacity(value))?,
+        Err(VarError::NotPresent) => 0,
+        Err(VarError::NotUnicode(value)) => {
+            return Err(ConfigError::InvalidCapacity(
+                value.to_string_lossy().into_owned(),
+            ))
+        }
+    };
+
+    Config::with_reserved(path, capacity)
+}
@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+use super::Config;
+
+/// Hands out one shared `Arc<str>` per distinct path, so configs built
+/// from the same path point at the same allocation.
+///
+/// The store keeps a strong reference to every path it has handed out, so
+/// a path outlives the configs using it until it is evicted explicitly
+/// with [`PathStore::clear_unused`]. Nothing is evicted on its own; a
+/// long-running process that interns many short-lived paths should call
+/// it from time to time.
+#[derive(Debug, Default)]
+pub struct PathStore(Mutex<HashSet<Arc<str>>>);
+
+impl PathStore {
+    pub fn new() -> Self {
+        PathStore::default()
+    }
+
+    /// Returns the stored `Arc<str>` for `path`, storing a new one the
+    /// first time a path is seen. Later calls only bump its count.
+    pub fn intern(&self, path: &str) -> Arc<str> {
+        let mut paths = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(interned) = paths.get(path) {
+            return Arc::clone(interned);
+        }
+
+        let interned: Arc<str> = Arc::from(path);
+        paths.insert(Arc::clone(&interned));
+        interned
+    }
+
+    /// Drops every path that only the store itself still refers to, and
+    /// returns how many were dropped. Paths still held by a config stay,
+    /// so they keep being shared with the next config built from them.
+    pub fn clear_unused(&self) -> usize {
+        let mut paths = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let before = paths.len();
+        paths.retain(|path| Arc::strong_count(path) > 1);
+        before - paths.len()
+    }
+
+    /// How many distinct paths are stored.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The process-wide store behind [`Config::with_interned_path`].
+    pub fn global() -> &'static PathStore {
+        static PATHS: OnceLock<PathStore> = OnceLock::new();
+        PATHS.get_or_init(PathStore::new)
+    }
+}
+
+/// A [`Config`] whose `path` is an interned `Arc<str>`. Cloning one copies
+/// the vector as usual, but the path is shared rather than copied, as
+/// the whole config is in `example_5`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InternedConfig {
+    path: Arc<str>,
+    pub very_long_vector: Vec<String>,
+}
+
+impl InternedConfig {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The shared allocation itself, for comparing with `Arc::ptr_eq`.
+    pub fn path_arc(&self) -> &Arc<str> {
+        &self.path
+    }
+
+    /// Copies the path out into a regular [`Config`], keeping the vector.
+    pub fn into_config(self) -> Config {
+        Config {
+            path: String::from(&*self.path),
+            very_long_vector: self.very_long_vector,
+        }
+    }
+}
+
+impl Config {
+    /// Builds an [`InternedConfig`] whose path comes from
+    /// [`PathStore::global`], so every config built from an equal `path`
+    /// shares one allocation.
+    pub fn with_interned_path(path: &str) -> InternedConfig {
+        InternedConfig {
+            path: PathStore::global().intern(path),
+            very_long_vector: Vec::new(),
+        }
+    }
+}
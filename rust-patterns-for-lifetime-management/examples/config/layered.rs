@@ -0,0 +1,47 @@
+use std::env::{self, VarError};
+use std::path::Path;
+
+use super::{Config, ConfigError};
+
+/// The file read by [`load`], relative to the current directory.
+pub const DEFAULT_FILE: &str = "config.toml";
+
+/// The environment variable that overrides `path`.
+pub const PATH_VAR: &str = "APP_PATH";
+
+/// Loads [`DEFAULT_FILE`]; see [`load_from`].
+pub fn load() -> Result<Config, ConfigError> {
+    load_from(DEFAULT_FILE)
+}
+
+/// Starts from `Config::default()`, overlays the TOML file at `file`,
+/// then overlays [`PATH_VAR`]. Each layer is moved into the next with
+/// [`Config::merge`], so later layers win.
+///
+/// A missing file or an unset [`PATH_VAR`] is skipped, but a file that
+/// exists and fails to load is an error, as is a [`PATH_VAR`] that is set
+/// but not valid Unicode.
+pub fn load_from(file: impl AsRef<Path>) -> Result<Config, ConfigError> {
+    let mut config = Config::default();
+
+    match Config::from_toml_path(file) {
+        Ok(from_file) => config = config.merge(from_file),
+        Err(ConfigError::MissingFile { .. }) => {}
+        Err(err) => return Err(err),
+    }
+
+    match env::var(PATH_VAR) {
+        Ok(path) => {
+            config = config.merge(Config {
+                path,
+                very_long_vector: Vec::new(),
+            })
+        }
+        Err(VarError::NotPresent) => {}
+        Err(VarError::NotUnicode(_)) => {
+            return Err(ConfigError::NonUnicodeEnvVar(String::from(PATH_VAR)))
+        }
+    }
+
+    Ok(config)
+}
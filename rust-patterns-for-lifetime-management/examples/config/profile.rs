@@ -0,0 +1,32 @@
+use super::{Config, ConfigBuilder, Environment};
+
+/// Items reserved by [`debug`]: enough for the examples, small enough to
+/// keep debug builds light.
+pub const DEBUG_CAPACITY: usize = 16;
+
+/// Items reserved by [`release`].
+pub const RELEASE_CAPACITY: usize = 1024;
+
+/// The baseline for the build profile this crate was compiled with:
+/// [`debug`] when `debug_assertions` are on, [`release`] otherwise.
+pub fn current() -> Config {
+    if cfg!(debug_assertions) {
+        debug()
+    } else {
+        release()
+    }
+}
+
+/// The dev environment's path with a small vector.
+pub fn debug() -> Config {
+    ConfigBuilder::from(Config::for_environment(Environment::Dev))
+        .reserve(DEBUG_CAPACITY)
+        .build()
+}
+
+/// The prod environment's path with a large vector.
+pub fn release() -> Config {
+    ConfigBuilder::from(Config::for_environment(Environment::Prod))
+        .reserve(RELEASE_CAPACITY)
+        .build()
+}
@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+
+/// Coalesces a burst of change notifications into a single reload.
+///
+/// Editors often write a file several times for one save. Rather than
+/// reloading on each write, the caller records every change with
+/// [`Debouncer::change`] and only reloads once [`Debouncer::ready`]
+/// reports that `quiet` has passed without another one.
+///
+/// Times are passed in rather than read from the clock, so the same
+/// sequence of calls always gives the same answers.
+#[derive(Debug)]
+pub struct Debouncer {
+    quiet: Duration,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(quiet: Duration) -> Self {
+        Debouncer {
+            quiet,
+            last_change: None,
+        }
+    }
+
+    /// Records a change at `now`, restarting the quiet period.
+    pub fn change(&mut self, now: Instant) {
+        self.last_change = Some(now);
+    }
+
+    /// How long after `now` the pending change becomes ready, or `None`
+    /// if nothing is pending.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.last_change
+            .map(|last| (last + self.quiet).saturating_duration_since(now))
+    }
+
+    /// Whether a change is pending and the quiet period has elapsed since
+    /// it. Returns `true` at most once per burst of changes.
+    pub fn ready(&mut self, now: Instant) -> bool {
+        let ready = self.remaining(now) == Some(Duration::ZERO);
+        if ready {
+            self.last_change = None;
+        }
+        ready
+    }
+}
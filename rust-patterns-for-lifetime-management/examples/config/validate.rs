@@ -0,0 +1,91 @@
+use std::fmt;
+use std::path::Path;
+
+use super::{Config, ConfigError};
+
+/// A single check a config has to pass. Rules only borrow the config.
+pub trait Rule {
+    fn check(&self, config: &Config) -> Result<(), ConfigError>;
+}
+
+/// Any closure with the right signature can be used as a rule directly.
+impl<F: Fn(&Config) -> Result<(), ConfigError>> Rule for F {
+    fn check(&self, config: &Config) -> Result<(), ConfigError> {
+        self(config)
+    }
+}
+
+/// Fails with [`ConfigError::EmptyPath`].
+#[derive(Clone, Copy, Debug)]
+pub struct NonEmptyPath;
+
+impl Rule for NonEmptyPath {
+    fn check(&self, config: &Config) -> Result<(), ConfigError> {
+        if config.path.is_empty() {
+            return Err(ConfigError::EmptyPath);
+        }
+        Ok(())
+    }
+}
+
+/// Fails with [`ConfigError::NonAbsolutePath`].
+#[derive(Clone, Copy, Debug)]
+pub struct AbsolutePath;
+
+impl Rule for AbsolutePath {
+    fn check(&self, config: &Config) -> Result<(), ConfigError> {
+        if !Path::new(&config.path).is_absolute() {
+            return Err(ConfigError::NonAbsolutePath(config.path.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Owns a list of rules and runs every one of them, in the order they
+/// were added.
+#[derive(Default)]
+pub struct Validator {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Validator {
+    /// A validator with no rules, which accepts every config.
+    pub fn new() -> Self {
+        Validator::default()
+    }
+
+    /// The rules behind [`Config::validate_all`], as a starting point for
+    /// adding more.
+    pub fn standard() -> Self {
+        Validator::new().rule(NonEmptyPath).rule(AbsolutePath)
+    }
+
+    /// Takes ownership of `rule` and appends it to the list.
+    pub fn rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Runs every rule and collects all of the failures.
+    pub fn check(&self, config: &Config) -> Result<(), Vec<ConfigError>> {
+        let errors: Vec<ConfigError> = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule.check(config).err())
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Validator")
+            .field("rules", &self.rules.len())
+            .finish()
+    }
+}
@@ -0,0 +1,63 @@
+use crate::config::Config;
+use crate::versioned::Versioned;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change<T> {
+    pub old: T,
+    pub new: T,
+}
+
+/// What changed between two configs. A field is `None` when it is the
+/// same on both sides.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    pub path: Option<Change<String>>,
+    pub len: Option<Change<usize>>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.path.is_none() && self.len.is_none()
+    }
+}
+
+impl Config {
+    /// Diffing only needs to borrow both configs; the only copies made
+    /// are of a changed `path`.
+    pub fn diff(&self, other: &Config) -> ConfigDiff {
+        let path = (self.path != other.path).then(|| Change {
+            old: self.path.clone(),
+            new: other.path.clone(),
+        });
+
+        let (old_len, new_len) = (self.very_long_vector.len(), other.very_long_vector.len());
+        let len = (old_len != new_len).then_some(Change {
+            old: old_len,
+            new: new_len,
+        });
+
+        ConfigDiff { path, len }
+    }
+}
+
+/// Replays `diff` onto `base`, moving `base` through rather than copying
+/// it. A changed path is copied in as `new`. The diff records only the
+/// vector's length, not its items, so a shrink truncates and a growth pads
+/// with empty strings. An empty diff hands `base` back untouched.
+pub fn patch(mut base: Config, diff: &ConfigDiff) -> Config {
+    if let Some(path) = &diff.path {
+        base.path.clone_from(&path.new);
+    }
+    if let Some(len) = &diff.len {
+        base.very_long_vector.resize(len.new, String::new());
+    }
+    base
+}
+
+impl Versioned<Config> {
+    /// Describes what changed going from `prev` to `self`, returned as
+    /// `(prev.version, self.version, diff)`.
+    pub fn diff_from(&self, prev: &Versioned<Config>) -> (u32, u32, ConfigDiff) {
+        (prev.version, self.version, prev.obj.diff(&self.obj))
+    }
+}
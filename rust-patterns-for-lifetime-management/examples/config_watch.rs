@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::config::reload::Debouncer;
+use crate::config::Config;
+use crate::shared::SharedConfig;
+
+/// Reloads `shared` from the TOML file at `path` every time the file is
+/// created or modified.
+///
+/// `shared` is moved into a background thread that outlives this call, as
+/// in `example_4`. The thread runs until the returned [`Handle`] is
+/// stopped or dropped. A file that fails to parse is logged and the
+/// previous config is kept.
+///
+/// The parent directory is watched rather than the file itself, so that
+/// editors which save by replacing the file are still picked up.
+pub fn watch(path: impl AsRef<Path>, shared: SharedConfig) -> notify::Result<Handle> {
+    watch_debounced(path, shared, Duration::ZERO)
+}
+
+/// Like [`watch`], but a burst of changes only causes one reload, once
+/// `quiet` has passed since the last of them. See [`Debouncer`].
+pub fn watch_debounced(
+    path: impl AsRef<Path>,
+    shared: SharedConfig,
+    quiet: Duration,
+) -> notify::Result<Handle> {
+    let path = path.as_ref().to_path_buf();
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+    // The watcher owns the only sender, so dropping it from the handle
+    // disconnects the channel and ends the loop below.
+    let thread = thread::spawn(move || {
+        let mut debouncer = Debouncer::new(quiet);
+
+        loop {
+            // Block until the next event, or only until the pending
+            // change is due if there is one.
+            let received = match debouncer.remaining(Instant::now()) {
+                Some(wait) => rx.recv_timeout(wait),
+                None => rx.recv().map_err(RecvTimeoutError::from),
+            };
+
+            match received {
+                Ok(Ok(event)) => {
+                    let is_change =
+                        matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+                    let is_ours = event
+                        .paths
+                        .iter()
+                        .any(|changed| changed.file_name() == path.file_name());
+
+                    if is_change && is_ours {
+                        debouncer.change(Instant::now());
+                    }
+                }
+                Ok(Err(err)) => eprintln!("config watch error: {err}"),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if debouncer.ready(Instant::now()) {
+                match Config::from_toml_path(&path) {
+                    Ok(config) => *shared.write() = config,
+                    Err(err) => eprintln!("keeping previous config: {err}"),
+                }
+            }
+        }
+    });
+
+    Ok(Handle {
+        watcher: Some(watcher),
+        thread: Some(thread),
+    })
+}
+
+/// Keeps a watcher started by [`watch`] or [`watch_debounced`] running.
+///
+/// Stopping it, either with [`Handle::stop`] or by dropping it, waits for
+/// the background thread to exit. Once that returns, `shared` will not be
+/// written to again, and a change still waiting out its quiet period is
+/// discarded.
+#[must_use = "the watcher stops as soon as the handle is dropped"]
+#[derive(Debug)]
+pub struct Handle {
+    watcher: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Handle {
+    /// The same as dropping the handle, spelled out at the call site.
+    pub fn stop(self) {}
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        drop(self.watcher.take());
+        if let Some(thread) = self.thread.take() {
+            // A panic in the watcher thread has already been reported, and
+            // re-raising it here could abort during an unwind.
+            let _ = thread.join();
+        }
+    }
+}
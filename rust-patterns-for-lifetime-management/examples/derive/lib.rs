@@ -0,0 +1,25 @@
+//! `#[derive(Versionable)]`, re-exported from
+//! `rust_patterns_for_lifetime_management::versioned` behind the `derive`
+//! feature.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Implements `Versionable` for the annotated type. The trait's provided
+/// methods do all the work, so the generated impl is empty; generics and
+/// where clauses are carried over as written.
+#[proc_macro_derive(Versionable)]
+pub fn derive_versionable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::rust_patterns_for_lifetime_management::versioned::Versionable
+            for #name #ty_generics #where_clause
+        {
+        }
+    }
+    .into()
+}
@@ -0,0 +1,49 @@
+use rust_patterns_for_lifetime_management::config::Config;
+use rust_patterns_for_lifetime_management::scope;
+use rust_patterns_for_lifetime_management::shared::SharedConfig;
+
+#[derive(Clone)]
+struct Worker {
+    config: SharedConfig,
+}
+
+fn main() {
+    // As in `example_5`, `config` is moved onto the heap once and every
+    // `Worker` receives a counted reference to it.
+    let config = SharedConfig::new(Config {
+        path: String::from("/etc/nginx/nginx.conf"),
+        very_long_vector: Vec::new(),
+    });
+
+    let worker_1 = Worker {
+        config: config.clone_handle(),
+    };
+    let worker_2 = Worker {
+        config: config.clone_handle(),
+    };
+
+    // Unlike `Arc<Config>`, the `RwLock` lets one holder mutate the shared
+    // value. The write guard is dropped at the end of this statement,
+    // releasing the lock for readers.
+    worker_1.config.write().path = String::from("/etc/nginx/reloaded.conf");
+
+    // Every other handle observes the change because they all point at
+    // the same heap allocation.
+    assert_eq!(worker_2.config.read().path, "/etc/nginx/reloaded.conf");
+    assert_eq!(config.read().path, "/etc/nginx/reloaded.conf");
+
+    // The override only lasts as long as `_guard` is in scope.
+    {
+        let _guard = scope::override_config(
+            &config,
+            Config {
+                path: String::from("/tmp/nginx.test.conf"),
+                very_long_vector: Vec::new(),
+            },
+        );
+        assert_eq!(worker_2.config.read().path, "/tmp/nginx.test.conf");
+    }
+
+    // Dropping the guard swapped the previous config back in.
+    assert_eq!(worker_2.config.read().path, "/etc/nginx/reloaded.conf");
+}
@@ -0,0 +1,99 @@
+use std::sync::{Arc, RwLock};
+
+use rust_patterns_for_lifetime_management::config::{Config, Environment};
+use rust_patterns_for_lifetime_management::worker::{Worker, WorkerPool};
+
+fn main() {
+    // `WorkerPool::new` takes ownership of `config` and wraps it in an
+    // `Arc` on our behalf.
+    let pool = WorkerPool::new(Config::for_environment(Environment::Prod), 100);
+
+    let workers = pool.workers();
+    assert_eq!(workers.len(), 100);
+
+    // Every worker points at the very same heap allocation; no worker has
+    // a copy of its own.
+    assert!(workers
+        .iter()
+        .all(|worker| Arc::ptr_eq(&worker.config, &workers[0].config)));
+
+    // One counted reference for the pool and one per worker. Cloning
+    // workers out of the pool only adds more references.
+    assert_eq!(pool.config_refcount(), 101);
+    let mut borrowed: Vec<Worker> = workers.to_vec();
+    assert_eq!(pool.config_refcount(), 201);
+    borrowed.truncate(50);
+    assert_eq!(pool.config_refcount(), 151);
+    drop(borrowed);
+    assert_eq!(pool.config_refcount(), 101);
+
+    let weak = workers[0].config_weak();
+    assert!(weak.upgrade().is_some());
+
+    // Iterating the pool only borrows its workers...
+    let paths: Vec<String> = pool
+        .iter()
+        .map(|worker| worker.config.read().unwrap().path.clone())
+        .collect();
+    assert_eq!(paths.len(), 100);
+
+    // ...whereas `into_iter` consumes the pool and hands each worker over.
+    let owned: Vec<Worker> = pool.into_iter().collect();
+    assert_eq!(owned.len(), 100);
+    assert!(weak.upgrade().is_some());
+
+    // Once the last worker is gone, nothing holds the config and it has
+    // been freed.
+    drop(owned);
+    assert!(weak.upgrade().is_none());
+
+    // Workers can also carry their own state. Each `Worker<u32>` owns a
+    // distinct task while sharing one config, and is given its id by us
+    // rather than by a pool.
+    let config = Arc::new(RwLock::new(Config::for_environment(Environment::Prod)));
+    let tasks: Vec<Worker<u32>> = (0..3)
+        .map(|id| Worker::new(id, Arc::clone(&config), 100 + id as u32))
+        .collect();
+
+    assert_eq!(tasks[1].task, 101);
+    assert_eq!(tasks[2].clone().task, 102);
+    assert_eq!(tasks[2].id(), 2);
+    assert!(Arc::ptr_eq(&tasks[0].config, &tasks[2].config));
+
+    // `into_shared` moves the workers into one `Arc<[Worker]>`, so handing
+    // the set to other owners clones a pointer, not a `Vec`.
+    let shared: Arc<[Worker]> =
+        WorkerPool::new(Config::for_environment(Environment::Prod), 1).into_shared();
+    let first = Arc::clone(&shared);
+    let second = Arc::clone(&shared);
+    assert_eq!(Arc::strong_count(&shared), 3);
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(first.len(), 1);
+
+    // `drain` consumes the pool; the workers keep the config alive.
+    let drained = WorkerPool::new(Config::for_environment(Environment::Prod), 3).drain();
+    assert_eq!(drained.len(), 3);
+    assert!(drained
+        .iter()
+        .all(|worker| Arc::ptr_eq(&worker.config, &drained[0].config)));
+    assert_eq!(Arc::strong_count(&drained[0].config), 3);
+
+    // `into_arc` moves the config into a fresh `Arc` with a single owner,
+    // so `from_arc` can move it on into the pool's lock without a copy.
+    let config = Config::for_environment(Environment::Prod).into_arc();
+    assert_eq!(Arc::strong_count(&config), 1);
+    assert_eq!(config.path, "/etc/nginx/nginx.conf");
+
+    // While another handle is alive the config cannot be moved, so the
+    // `Arc` comes back instead of being cloned behind our back.
+    let other = Arc::clone(&config);
+    let config = WorkerPool::from_arc(config, 2).unwrap_err();
+    assert!(Arc::ptr_eq(&config, &other));
+    drop(other);
+
+    let pool = WorkerPool::from_arc(config, 2).unwrap();
+    assert_eq!(
+        pool.workers()[1].config.read().unwrap().path,
+        "/etc/nginx/nginx.conf"
+    );
+}
@@ -0,0 +1,115 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use reqwest::Client;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use rust_patterns_for_lifetime_management::config::{Config, Environment};
+use rust_patterns_for_lifetime_management::worker::{Worker, WorkerPool};
+
+/// Answers every connection with an empty `200 OK`, standing in for the
+/// real server from `example_4`.
+async fn mock_server() -> String {
+    flaky_server(0).await
+}
+
+/// Like [`mock_server`], but the first `failures` connections are
+/// answered with `503 Service Unavailable`.
+async fn flaky_server(failures: usize) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    // The listener is moved into the spawned task, which outlives this
+    // function.
+    tokio::spawn(async move {
+        let mut failures = failures;
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+
+            // Read until the end of the request headers; a GET has no body.
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..read]);
+            }
+
+            let status = if failures > 0 {
+                failures -= 1;
+                "503 Service Unavailable"
+            } else {
+                "200 OK"
+            };
+            let response =
+                format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    url
+}
+
+#[tokio::main]
+async fn main() {
+    let url = mock_server().await;
+
+    let pool = WorkerPool::new(Config::for_environment(Environment::Prod), 2);
+
+    // `run` only borrows the worker; the worker keeps its client for the
+    // next request.
+    let worker = &pool.workers()[0];
+    let response = worker.run(&url).await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = worker.run(&url).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(worker.runs(), 2);
+    assert_eq!(pool.total_runs(), 2);
+
+    // Two `503`s are retried with backoff before the `200` arrives. The
+    // standalone worker takes the next id after the pool's two, so it
+    // cannot be mistaken for one of them.
+    let flaky = flaky_server(2).await;
+    let retrying = Worker::new(2, Arc::clone(&pool.workers()[0].config), ());
+    assert!(pool.get(retrying.id()).is_none());
+    let response = retrying
+        .run_with_retry(&flaky, 3, Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(retrying.runs(), 3);
+
+    // With no retries left, the last `503` is handed back as is.
+    let flaky = flaky_server(2).await;
+    let response = retrying
+        .run_with_retry(&flaky, 1, Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+
+    // An invalid URL cannot succeed, so it is not retried at all.
+    let runs = retrying.runs();
+    assert!(retrying
+        .run_with_retry("not a url", 3, Duration::from_secs(60))
+        .await
+        .is_err());
+    assert_eq!(retrying.runs(), runs + 1);
+
+    // A client built by the caller can be injected, and clones of it
+    // share one connection pool across workers.
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+    let config = Arc::new(RwLock::new(Config::for_environment(Environment::Prod)));
+    let injected: Vec<Worker> = (0..2)
+        .map(|id| Worker::with_client(id, Arc::clone(&config), (), client.clone()))
+        .collect();
+    for worker in &injected {
+        assert_eq!(worker.run(&url).await.unwrap().status(), 200);
+    }
+}
@@ -0,0 +1,80 @@
+use std::env;
+use std::fs;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rust_patterns_for_lifetime_management::config::reload::Debouncer;
+use rust_patterns_for_lifetime_management::config::Config;
+use rust_patterns_for_lifetime_management::config_watch;
+use rust_patterns_for_lifetime_management::shared::SharedConfig;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn main() {
+    let dir = env::temp_dir().join(format!("example_13-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("nginx.toml");
+    fs::write(&path, "path = \"/etc/nginx/nginx.conf\"\n").unwrap();
+
+    let shared = SharedConfig::new(Config::from_toml_path(&path).unwrap());
+
+    // The watcher thread receives its own handle; `main` keeps `shared`
+    // to observe the reload.
+    let watcher = config_watch::watch(&path, shared.clone_handle()).unwrap();
+
+    fs::write(&path, "path = \"/etc/nginx/reloaded.conf\"\n").unwrap();
+
+    let started = Instant::now();
+    while shared.read().path != "/etc/nginx/reloaded.conf" {
+        assert!(started.elapsed() < TIMEOUT, "config was not reloaded");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // Once stopped, the thread has exited and later edits are ignored.
+    watcher.stop();
+    fs::write(&path, "path = \"/etc/nginx/stopped.conf\"\n").unwrap();
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(shared.read().path, "/etc/nginx/reloaded.conf");
+
+    // Three changes 50ms apart fall inside one 200ms quiet period, so
+    // they add up to a single reload.
+    let quiet = Duration::from_millis(200);
+    let start = Instant::now();
+    let mut debouncer = Debouncer::new(quiet);
+    let mut reloads = 0;
+    for ms in (0..=600).step_by(10) {
+        let now = start + Duration::from_millis(ms);
+        if [0, 50, 100].contains(&ms) {
+            debouncer.change(now);
+        }
+        if debouncer.ready(now) {
+            reloads += 1;
+            assert_eq!(ms, 300);
+        }
+    }
+    assert_eq!(reloads, 1);
+
+    // The debounced watcher ends up with the last of several rapid writes.
+    let debounced = dir.join("debounced.toml");
+    fs::write(&debounced, "path = \"/etc/nginx/nginx.conf\"\n").unwrap();
+    let shared = SharedConfig::new(Config::from_toml_path(&debounced).unwrap());
+    let _watcher = config_watch::watch_debounced(&debounced, shared.clone_handle(), quiet).unwrap();
+
+    for i in 1..=3 {
+        fs::write(
+            &debounced,
+            format!("path = \"/etc/nginx/nginx.{i}.conf\"\n"),
+        )
+        .unwrap();
+    }
+
+    let started = Instant::now();
+    while shared.read().path != "/etc/nginx/nginx.3.conf" {
+        assert!(started.elapsed() < TIMEOUT, "config was not reloaded");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
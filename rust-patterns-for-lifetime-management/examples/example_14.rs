@@ -0,0 +1,32 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use rust_patterns_for_lifetime_management::config::{Config, ConfigError};
+
+fn main() {
+    let dir = env::temp_dir().join(format!("example_14-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let nginx = dir.join("nginx.yaml");
+    fs::write(&nginx, "path: /etc/nginx/nginx.conf\n").unwrap();
+
+    let no_path = dir.join("no_path.yaml");
+    fs::write(&no_path, "other: 1\n").unwrap();
+
+    // Same as the TOML loader in `example_9`: only the file path is
+    // borrowed, and the returned `Config` owns its own `path`.
+    let config = Config::from_yaml_path(&nginx).unwrap();
+    assert_eq!(config.path, "/etc/nginx/nginx.conf");
+
+    assert!(matches!(
+        Config::from_yaml_path(&no_path),
+        Err(ConfigError::MissingPath)
+    ));
+    assert!(matches!(
+        Config::from_yaml_path(dir.join("missing.yaml")),
+        Err(ConfigError::MissingFile { .. })
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,40 @@
+use rust_patterns_for_lifetime_management::versioned::{
+    next_version, parse_version_tag, version, ParseVersionError,
+};
+
+// Only the `core` half of `versioned` is used here, so this example also
+// builds against the library with `--no-default-features`, where the
+// library itself is `no_std`.
+fn main() {
+    // A fixed-size buffer stands in for `Config`, which needs an allocator.
+    let first = version([0u8; 4]);
+    let mut second = next_version([1u8, 2, 3, 4], &first);
+    assert_eq!(second.version, 2);
+
+    // `DerefMut` and `bump` record an in-place edit as the next version.
+    second[0] = 9;
+    second.bump();
+    assert_eq!(second.version, 3);
+
+    // `map` moves the buffer into the closure and keeps the version.
+    let sum = second.map(|bytes| bytes.iter().map(|&b| u32::from(b)).sum::<u32>());
+    assert_eq!(sum.version, 3);
+    assert_eq!(sum.into_inner(), 18);
+
+    // `replace` hands the displaced value back without any allocation.
+    let mut flag = version(false);
+    assert!(!flag.replace(true));
+    assert_eq!(flag.version, 2);
+
+    // Version tags parse without allocating.
+    assert_eq!(parse_version_tag("v3"), Ok(3));
+    assert_eq!(parse_version_tag("V3"), Ok(3));
+    assert_eq!(parse_version_tag("3"), Ok(3));
+    assert_eq!(parse_version_tag("ver3"), Err(ParseVersionError::Invalid));
+    assert_eq!(parse_version_tag(""), Err(ParseVersionError::Invalid));
+    assert_eq!(parse_version_tag("v"), Err(ParseVersionError::Invalid));
+    assert_eq!(
+        parse_version_tag("v4294967296"),
+        Err(ParseVersionError::TooLarge)
+    );
+}
@@ -0,0 +1,33 @@
+use rust_patterns_for_lifetime_management::config::Config;
+use rust_patterns_for_lifetime_management::versioned::compressed::CompressedHistory;
+
+fn main() {
+    let configs: Vec<Config> = (1..=4)
+        .map(|i| Config {
+            path: format!("/etc/nginx/nginx.{i}.conf"),
+            very_long_vector: vec![format!("worker_processes {i};"); 1000],
+        })
+        .collect();
+
+    // Each push moves a config into the history; the one it displaces is
+    // compressed, so only the latest is kept as a live `Config`.
+    let mut history = CompressedHistory::new();
+    for config in configs.iter().cloned() {
+        history.push(config).unwrap();
+    }
+    assert_eq!(history.len(), 4);
+    assert_eq!(history.latest().unwrap().version, 4);
+
+    // `Debug` summarizes the archive instead of dumping compressed bytes.
+    assert!(format!("{history:?}").starts_with("CompressedHistory { archived: 3, "));
+
+    // `get` decompresses an archived version into a new owned value that
+    // matches what was pushed. A version that was never pushed is
+    // `Ok(None)`, not an error.
+    let restored = history.get(2).unwrap().unwrap();
+    assert_eq!(restored.version, 2);
+    assert_eq!(restored.obj, configs[1]);
+
+    assert_eq!(history.get(4).unwrap().unwrap().obj, configs[3]);
+    assert!(history.get(5).unwrap().is_none());
+}
@@ -0,0 +1,21 @@
+use rust_patterns_for_lifetime_management::config::Config;
+use rust_patterns_for_lifetime_management::worker::{Worker, WorkerPool};
+
+fn main() {
+    let pool = WorkerPool::new(
+        Config {
+            path: String::from("/etc/nginx/nginx.conf"),
+            very_long_vector: Vec::new(),
+        },
+        8,
+    );
+
+    // `par_map` lends each worker to a rayon thread. Sharing the config
+    // across threads is safe because `Arc<Config>` is `Send + Sync`.
+    let path_len = |worker: &Worker| worker.config.read().unwrap().path.len();
+    let parallel = pool.par_map(path_len);
+    let sequential: Vec<usize> = pool.iter().map(path_len).collect();
+
+    assert_eq!(parallel, sequential);
+    assert_eq!(parallel.len(), 8);
+}
@@ -0,0 +1,40 @@
+use rust_patterns_for_lifetime_management::versioned::{Versionable, Versioned};
+
+/// Not a `Config`, and nothing in the library knows about it.
+#[derive(Debug, PartialEq, Versionable)]
+struct Upstream {
+    host: String,
+    port: u16,
+}
+
+/// Generic parameters are carried over to the derived impl.
+#[derive(Versionable)]
+struct Tagged<T> {
+    tag: T,
+}
+
+fn main() {
+    // `to_versioned` moves the value into a new lineage at version 1.
+    let mut upstream: Versioned<Upstream> = Upstream {
+        host: String::from("127.0.0.1"),
+        port: 8080,
+    }
+    .to_versioned();
+    assert_eq!(upstream.version, 1);
+
+    // An edit through `DerefMut`, recorded as the next version.
+    upstream.port = 8081;
+    Upstream::bump(&mut upstream);
+    assert_eq!(upstream.version, 2);
+    assert_eq!(
+        upstream.into_inner(),
+        Upstream {
+            host: String::from("127.0.0.1"),
+            port: 8081,
+        }
+    );
+
+    let tagged = Tagged { tag: "blue" }.to_versioned();
+    assert_eq!(tagged.version, 1);
+    assert_eq!(tagged.tag, "blue");
+}
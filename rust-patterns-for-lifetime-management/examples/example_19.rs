@@ -0,0 +1,18 @@
+use rust_patterns_for_lifetime_management::config::Config;
+
+fn main() {
+    let config = Config {
+        path: String::from("/etc/nginx/nginx.conf"),
+        very_long_vector: Vec::new(),
+    };
+
+    // Matching only borrows `config`, so it stays usable afterwards.
+    assert!(config.matches_glob("/etc/nginx/*.conf"));
+    assert!(config.matches_glob("/etc/**/nginx.conf"));
+    assert!(!config.matches_glob("/etc/apache2/*.conf"));
+
+    // `***` is not a valid pattern; it matches nothing rather than
+    // panicking.
+    assert!(!config.matches_glob("/etc/***/nginx.conf"));
+    assert_eq!(config.path, "/etc/nginx/nginx.conf");
+}
@@ -0,0 +1,29 @@
+// `save_config_version` from `example_2`, extended to take the previous
+// version. `example_2` and `example_3` keep the one-argument form from the
+// post; this is the library's version, which is kept for compatibility.
+#![allow(deprecated)]
+
+use rust_patterns_for_lifetime_management::config::Config;
+use rust_patterns_for_lifetime_management::versioned::save_config_version;
+
+fn main() {
+    let config = Config {
+        path: String::from("/etc/nginx/nginx.conf"),
+        very_long_vector: Vec::new(),
+    };
+
+    // The first version has nothing before it, so it starts at 1.
+    let version_1 = save_config_version(config.clone(), None);
+
+    // Each subsequent version borrows the previous `Versioned` just long
+    // enough to read its `version` number, while still receiving its own
+    // `clone`'d copy of `config` to keep.
+    let version_2 = save_config_version(config.clone(), Some(&version_1));
+    let version_3 = save_config_version(config.clone(), Some(&version_2));
+
+    // `main` still owns `config`, and every version owns its own copy.
+    assert_eq!(version_1.version, 1);
+    assert_eq!(version_2.version, 2);
+    assert_eq!(version_3.version, 3);
+    assert_eq!(version_3.obj.path, config.path);
+}
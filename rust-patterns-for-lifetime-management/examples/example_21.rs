@@ -0,0 +1,64 @@
+use rust_patterns_for_lifetime_management::config::{Config, Environment};
+use rust_patterns_for_lifetime_management::versioned::{
+    RollbackError, SquashError, VersionHistory,
+};
+
+fn main() {
+    // Rolling back clones the old content into a brand new version, so the
+    // history only ever grows.
+    let mut history: VersionHistory<Config> = (1..=3)
+        .map(|i| Config::try_from(format!("/etc/nginx/nginx.{i}.conf").as_str()).unwrap())
+        .collect();
+    history.rollback(1).unwrap();
+
+    let latest = history.latest().unwrap();
+    assert_eq!(latest.version, 4);
+    assert_eq!(latest.path, "/etc/nginx/nginx.1.conf");
+    assert_eq!(history.len(), 4);
+    assert_eq!(history.rollback(9), Err(RollbackError::NotFound(9)));
+
+    // Squashing 2..=4 keeps only version 4's content, and remembers that
+    // it started at 2.
+    let mut squashed: VersionHistory<String> = (1..=5)
+        .map(|i| format!("/etc/nginx/nginx.{i}.conf"))
+        .collect();
+    squashed.squash(2, 4).unwrap();
+    assert_eq!(squashed.len(), 3);
+    assert!(squashed.get(2).is_none());
+    assert!(squashed.get(3).is_none());
+    assert_eq!(squashed.get(4).unwrap().obj, "/etc/nginx/nginx.4.conf");
+    assert_eq!(squashed.squashed_from(4), Some(2));
+    assert_eq!(squashed.latest().unwrap().obj, "/etc/nginx/nginx.5.conf");
+
+    // Squashing over a squash extends the recorded range.
+    squashed.squash(4, 5).unwrap();
+    assert_eq!(squashed.squashed_from(5), Some(2));
+    assert_eq!(squashed.len(), 2);
+
+    assert_eq!(
+        squashed.squash(5, 1),
+        Err(SquashError::InvalidRange { from: 5, to: 1 })
+    );
+    assert_eq!(squashed.squash(3, 5), Err(SquashError::NotFound(3)));
+
+    // Identical configs share a checksum, so pushing one again is skipped.
+    let nginx = Config::for_environment(Environment::Prod);
+    let apache = Config::try_from("/etc/apache2/apache2.conf").unwrap();
+    assert_eq!(nginx.checksum(), nginx.clone().checksum());
+    assert_ne!(nginx.checksum(), apache.checksum());
+
+    let mut history = VersionHistory::new();
+    assert!(history.push_if_changed(nginx.clone()));
+    assert!(!history.push_if_changed(nginx));
+    assert!(history.push_if_changed(apache));
+    assert_eq!(history.len(), 2);
+
+    // A change to an item's contents counts, even at the same length.
+    let before = Config::with_items("/etc/nginx/nginx.conf", vec![String::from("a")]);
+    let after = Config::with_items("/etc/nginx/nginx.conf", vec![String::from("b")]);
+    assert_ne!(before.checksum(), after.checksum());
+    assert!(history.push_if_changed(before));
+    assert!(history.push_if_changed(after));
+    assert_eq!(history.latest().unwrap().items(), ["b"]);
+    assert_eq!(history.len(), 4);
+}
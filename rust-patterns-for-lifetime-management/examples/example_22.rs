@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use rust_patterns_for_lifetime_management::config::{is_valid_config, Config, Environment};
+use rust_patterns_for_lifetime_management::versioned::{next_version, version, Versioned};
+
+fn main() {
+    // `map` consumes the `Versioned<Config>` and moves the config into
+    // the closure, which keeps only what it needs.
+    let capacity = next_version(
+        Config::with_reserved("/etc/nginx/nginx.conf", 16).unwrap(),
+        &version(Config::for_environment(Environment::Prod)),
+    )
+    .map(|config| config.very_long_vector.capacity());
+
+    assert_eq!(capacity.version, 2);
+    assert!(capacity.obj >= 16);
+
+    let versioned_config = version(Config::for_environment(Environment::Prod));
+
+    // `as_ref` lends out the inner config while `main` keeps ownership of
+    // the wrapper...
+    assert!(is_valid_config(versioned_config.as_ref()));
+
+    // ...whereas `into_inner` moves the config out, after which
+    // `versioned_config` is no longer a valid binding.
+    let config: Config = versioned_config.into_inner();
+    assert_eq!(config.path, "/etc/nginx/nginx.conf");
+
+    // `into` moves the config into a version 1 wrapper.
+    let versioned: Versioned<Config> = config.into();
+    assert_eq!(versioned.version, 1);
+
+    // `version` and `next_version` work for any type, not just `Config`.
+    let bytes = version(vec![0u8, 1, 2]);
+    let bytes = next_version(vec![3u8, 4, 5], &bytes);
+    assert_eq!(bytes.version, 2);
+    assert_eq!(bytes.obj, [3, 4, 5]);
+
+    // `try_map` keeps the version when the closure succeeds...
+    let port = next_version(String::from("8080"), &version(String::from("80")));
+    let port = port.try_map(|port| port.parse::<u16>()).unwrap();
+    assert_eq!(port.version, 2);
+    assert_eq!(port.obj, 8080);
+
+    // ...and hands back the error, not the wrapper, when it fails.
+    let invalid = version(String::from("http")).try_map(|port| port.parse::<u16>());
+    assert!(invalid.is_err());
+
+    // Mutate through `DerefMut`, then record the change with `bump`.
+    let mut versioned = version(Config::for_environment(Environment::Prod));
+    versioned.path = String::from("/etc/nginx/edited.conf");
+    versioned.bump();
+    versioned
+        .very_long_vector
+        .push(String::from("worker_processes 4;"));
+    versioned.bump();
+    assert_eq!(versioned.version, 3);
+    assert_eq!(versioned.path, "/etc/nginx/edited.conf");
+
+    // `replace` moves the old config back out to the caller.
+    let mut versioned = version(Config::for_environment(Environment::Prod));
+    let old = versioned.replace(Config::try_from("/etc/nginx/replaced.conf").unwrap());
+    assert_eq!(old.path, "/etc/nginx/nginx.conf");
+    assert_eq!(versioned.path, "/etc/nginx/replaced.conf");
+    assert_eq!(versioned.version, 2);
+
+    // `fork` only borrows the original and clones its config into a
+    // lineage of its own.
+    let original = Versioned::with_timestamp(
+        Config::with_items(
+            "/etc/nginx/nginx.conf",
+            vec![String::from("worker_processes 4;")],
+        ),
+        5,
+        SystemTime::now(),
+    );
+    let fork = original.fork();
+    assert_eq!(fork.version, 1);
+    assert_eq!(fork.obj, original.obj);
+    assert_eq!(original.version, 5);
+
+    // The audit log callback owns its own handle to `events` and only
+    // borrows each new config, so nothing is cloned to log it.
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&events);
+    let mut audited = version(Config::for_environment(Environment::Prod)).audit_log(
+        move |version, config: &Config| {
+            log.borrow_mut().push((version, config.path.clone()));
+        },
+    );
+    audited.obj_mut().path = String::from("/etc/nginx/edited.conf");
+    audited.bump();
+    audited.replace(Config::try_from("/etc/nginx/replaced.conf").unwrap());
+    audited.bump();
+    assert_eq!(audited.version, 4);
+    assert_eq!(
+        *events.borrow(),
+        [
+            (2, String::from("/etc/nginx/edited.conf")),
+            (3, String::from("/etc/nginx/replaced.conf")),
+            (4, String::from("/etc/nginx/replaced.conf")),
+        ]
+    );
+}
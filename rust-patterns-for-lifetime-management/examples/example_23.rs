@@ -0,0 +1,111 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+use rust_patterns_for_lifetime_management::config::{Config, Environment};
+use rust_patterns_for_lifetime_management::versioned::{
+    is_valid_versioned, next_version, version, Versioned,
+};
+
+fn main() {
+    // Sorting only compares version numbers, never the configs themselves.
+    let mut out_of_order: Vec<Versioned<Config>> = [3, 1, 2]
+        .into_iter()
+        .map(|version| {
+            let path = format!("/etc/nginx/nginx.{version}.conf");
+            let config = Config::try_from(path.as_str()).unwrap();
+            Versioned::with_timestamp(config, version, SystemTime::now())
+        })
+        .collect();
+    out_of_order.sort();
+
+    let versions: Vec<u32> = out_of_order.iter().map(|v| v.version).collect();
+    assert_eq!(versions, [1, 2, 3]);
+    assert_eq!(out_of_order[0].path, "/etc/nginx/nginx.1.conf");
+
+    // Equal numbers compare `Equal` even though the contents differ.
+    let renamed = Versioned::with_timestamp(
+        Config::try_from("/etc/nginx/renamed.conf").unwrap(),
+        1,
+        SystemTime::now(),
+    );
+    assert_eq!(out_of_order[0].cmp(&renamed), Ordering::Equal);
+    assert_ne!(out_of_order[0], renamed);
+
+    // Version comparisons never look at the contents.
+    let v1 = version(String::from("/etc/nginx/nginx.conf"));
+    let v2 = next_version(String::from("/etc/nginx/next.conf"), &v1);
+    let other_v2 = next_version(String::from("/etc/apache2/apache2.conf"), &v1);
+    assert!(v2.is_newer_than(&v1));
+    assert!(!v1.is_newer_than(&v2));
+    assert!(!v2.is_newer_than(&other_v2));
+    assert!(v2.is_same_version(&other_v2));
+    assert!(!v1.is_same_version(&v2));
+
+    // `latest_of` keeps the newer of two lineages and drops the other.
+    let local = Versioned::with_timestamp(String::from("local"), 3, SystemTime::now());
+    let remote = Versioned::with_timestamp(String::from("remote"), 5, SystemTime::now());
+    let newest = local.latest_of(remote);
+    assert_eq!(newest.version, 5);
+    assert_eq!(newest.obj, "remote");
+
+    let tie = Versioned::with_timestamp(String::from("tie"), 5, SystemTime::now());
+    assert_eq!(newest.latest_of(tie).obj, "remote");
+
+    // Every version records when it was saved.
+    let earlier = version(String::from("/etc/nginx/nginx.conf"));
+    let later = next_version(String::from("/etc/nginx/reloaded.conf"), &earlier);
+    assert!(later.created_at() >= earlier.created_at());
+
+    let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let fixed = Versioned::with_timestamp(String::from("/etc/nginx/nginx.conf"), 7, at);
+    assert_eq!(fixed.created_at(), at);
+
+    // The timestamp is not part of equality.
+    let restamped =
+        Versioned::with_timestamp(String::from("/etc/nginx/nginx.conf"), 7, SystemTime::now());
+    assert_eq!(fixed, restamped);
+    assert_eq!(fixed.map(|path| path.len()).created_at(), at);
+
+    // A good path is not enough if the version number is invalid.
+    let nginx = Config::for_environment(Environment::Prod);
+    assert!(is_valid_versioned(&version(nginx.clone())));
+    assert!(!is_valid_versioned(&Versioned::with_timestamp(
+        nginx,
+        0,
+        SystemTime::now()
+    )));
+
+    // `Display` gives a compact form for logs.
+    let mut logged = version(Config::with_items(
+        "/etc/nginx/nginx.conf",
+        vec![String::from("worker_processes 4;")],
+    ));
+    logged.bump();
+    assert_eq!(
+        logged.obj.to_string(),
+        r#"Config { path: "/etc/nginx/nginx.conf", items: 1 }"#
+    );
+    assert_eq!(
+        logged.to_string(),
+        r#"v2: Config { path: "/etc/nginx/nginx.conf", items: 1 }"#
+    );
+
+    // A set of versioned configs can be searched with a plain `&Config`,
+    // through `Borrow<Config>`.
+    let nginx = Config::for_environment(Environment::Prod);
+    let apache = Config::with_reserved("/etc/apache2/apache2.conf", 16).unwrap();
+    let mut seen: HashSet<Versioned<Config>> = HashSet::new();
+    seen.insert(version(nginx.clone()));
+    seen.insert(next_version(apache, &version(nginx.clone())));
+
+    assert_eq!(seen.get(&nginx).unwrap().version, 1);
+    // Capacity affects neither equality nor the hash.
+    let lookup = Config::try_from("/etc/apache2/apache2.conf").unwrap();
+    assert_eq!(seen.get(&lookup).unwrap().version, 2);
+    assert!(!seen.contains(&Config::default()));
+
+    // `AsRef<Config>` serves APIs that take `impl AsRef<Config>`.
+    let path_of = |config: &dyn AsRef<Config>| config.as_ref().path.clone();
+    assert_eq!(path_of(&version(nginx)), "/etc/nginx/nginx.conf");
+}
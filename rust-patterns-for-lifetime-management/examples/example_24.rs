@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use rust_patterns_for_lifetime_management::config::{Config, Environment};
+use rust_patterns_for_lifetime_management::worker::WorkerPool;
+
+fn main() {
+    // Resizing reuses the single shared config in both directions.
+    let mut pool = WorkerPool::new(Config::for_environment(Environment::Prod), 2);
+    let shared = Arc::clone(&pool.workers()[0].config);
+
+    pool.resize(5);
+    assert_eq!(pool.workers().len(), 5);
+    assert!(pool
+        .iter()
+        .all(|worker| Arc::ptr_eq(&worker.config, &shared)));
+
+    pool.resize(1);
+    assert_eq!(pool.workers().len(), 1);
+    assert!(Arc::ptr_eq(&pool.workers()[0].config, &shared));
+
+    // Ids are handed out in order and never reused, so they stay valid
+    // across a shrink and regrow.
+    let mut pool = WorkerPool::new(Config::for_environment(Environment::Prod), 3);
+    assert_eq!(pool.get(1).unwrap().id(), 1);
+    assert!(pool.get(99).is_none());
+
+    pool.resize(2);
+    pool.resize(3);
+    assert!(pool.get(2).is_none());
+    assert_eq!(pool.workers()[2].id(), 3);
+
+    // The builder collects options before the config is moved into the pool.
+    let built = WorkerPool::builder()
+        .config(Config::for_environment(Environment::Prod))
+        .size(4)
+        .with_metrics(true)
+        .build();
+    assert_eq!(built.workers().len(), 4);
+    assert_eq!(built.config_refcount(), 5);
+
+    // A valid shared config means every worker is healthy...
+    assert_eq!(built.health_check(), Ok(()));
+
+    // ...and an empty path fails on every worker, since they all share it.
+    let unhealthy = WorkerPool::new(Config::default(), 3);
+    assert_eq!(unhealthy.health_check(), Err(vec![0, 1, 2]));
+
+    let unconfigured = WorkerPool::builder().build();
+    assert!(unconfigured.workers().is_empty());
+    assert_eq!(unconfigured.health_check(), Ok(()));
+}
@@ -0,0 +1,69 @@
+use std::thread;
+use std::time::Duration;
+
+use rust_patterns_for_lifetime_management::config::{Config, Environment};
+use rust_patterns_for_lifetime_management::worker::rolling::RollingPool;
+use rust_patterns_for_lifetime_management::worker::WorkerPool;
+
+fn main() {
+    // Reloading replaces the config for every existing worker at once.
+    let pool = WorkerPool::new(Config::for_environment(Environment::Prod), 100);
+    let weak = pool.workers()[0].config_weak();
+    pool.reload(Config::try_from("/etc/nginx/reloaded.conf").unwrap());
+
+    assert_eq!(
+        pool.workers()[42].config.read().unwrap().path,
+        "/etc/nginx/reloaded.conf"
+    );
+    // The new config is moved into the same lock, not a new allocation.
+    assert!(weak.upgrade().is_some());
+
+    // `config_mut` edits the shared config in place. Workers cannot read
+    // it while the guard is held, and see every edit once it is dropped.
+    let pool = WorkerPool::new(Config::for_environment(Environment::Prod), 3);
+    {
+        let mut config = pool.config_mut();
+        config.path = String::from("/etc/nginx/edited.conf");
+        config
+            .very_long_vector
+            .push(String::from("worker_processes 4;"));
+        assert!(pool.workers()[0].config.try_read().is_err());
+    }
+    assert_eq!(pool.config_read().path, "/etc/nginx/edited.conf");
+    assert!(pool
+        .iter()
+        .all(|worker| worker.config.read().unwrap().path == "/etc/nginx/edited.conf"));
+
+    // A `reload` racing an open guard waits for it, rather than being
+    // overwritten when the guard is released.
+    thread::scope(|scope| {
+        let mut config = pool.config_mut();
+        scope.spawn(|| pool.reload(Config::try_from("/etc/nginx/reloaded.conf").unwrap()));
+        thread::sleep(Duration::from_millis(20));
+        config.path = String::from("/etc/nginx/edited_again.conf");
+    });
+    assert_eq!(pool.config_read().path, "/etc/nginx/reloaded.conf");
+    assert!(pool.config_read().very_long_vector.is_empty());
+
+    // A rolling reload moves two workers at a time; the other two keep
+    // serving the old config until the pause between batches is over.
+    let rolling = RollingPool::new(Config::for_environment(Environment::Prod), 4);
+    let reloaded = |pool: &RollingPool| {
+        pool.iter()
+            .filter(|worker| worker.config.load().path == "/etc/nginx/rolled.conf")
+            .count()
+    };
+    let mut pauses = Vec::new();
+    rolling.rolling_reload(
+        Config::try_from("/etc/nginx/rolled.conf").unwrap(),
+        2,
+        |updated| pauses.push((updated, reloaded(&rolling))),
+    );
+    assert_eq!(pauses, [(2, 2)]);
+    assert_eq!(reloaded(&rolling), 4);
+    assert_eq!(
+        rolling.workers()[0].config.load().path,
+        "/etc/nginx/rolled.conf"
+    );
+    assert_eq!(rolling.workers()[3].id(), 3);
+}
@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use tokio::time;
+
+use rust_patterns_for_lifetime_management::config::{Config, Environment};
+use rust_patterns_for_lifetime_management::worker::WorkerPool;
+
+#[tokio::main]
+async fn main() {
+    // Each spawned task owns its own clone of a worker, so nothing is
+    // borrowed across the spawn boundary.
+    let pool = WorkerPool::new(Config::for_environment(Environment::Prod), 10);
+    let paths = pool
+        .spawn_all(|worker, _| async move { worker.config.read().unwrap().path.clone() })
+        .await;
+
+    assert_eq!(paths.len(), 10);
+    assert!(paths.iter().all(|path| path == "/etc/nginx/nginx.conf"));
+
+    // Blocking work runs on tokio's blocking threads, each with its own
+    // clone of a worker.
+    let paths = pool
+        .spawn_blocking_all(|worker| worker.config.read().unwrap().path.clone())
+        .await;
+    assert_eq!(paths.len(), 10);
+    assert!(paths.iter().all(|path| path == "/etc/nginx/nginx.conf"));
+
+    // A task that would sleep for a minute instead returns as soon as the
+    // pool is shut down.
+    let started = Instant::now();
+    let (finished, ()) = tokio::join!(
+        pool.spawn_all(|_, shutdown| async move {
+            tokio::select! {
+                _ = time::sleep(Duration::from_secs(60)) => false,
+                _ = shutdown.cancelled() => true,
+            }
+        }),
+        async {
+            time::sleep(Duration::from_millis(10)).await;
+            pool.shutdown();
+        },
+    );
+
+    assert!(finished.iter().all(|cancelled| *cancelled));
+
+    // The pool stays shut down, so later tasks are cancelled from the start.
+    let cancelled = pool
+        .spawn_all(|_, shutdown| async move { shutdown.is_cancelled() })
+        .await;
+    assert!(cancelled.iter().all(|cancelled| *cancelled));
+
+    // Four rounds of spawning over ten workers.
+    assert_eq!(pool.total_runs(), 40);
+
+    // With metrics disabled the same work goes uncounted.
+    let quiet = WorkerPool::builder()
+        .config(Config::for_environment(Environment::Prod))
+        .size(4)
+        .with_metrics(false)
+        .build();
+    quiet.spawn_all(|_, _| async {}).await;
+    assert_eq!(quiet.total_runs(), 0);
+
+    // Map every worker to 1 and sum the results as they complete.
+    let total = pool
+        .map_reduce(|_| async { 1 }, 0, |sum, one| sum + one)
+        .await;
+    assert_eq!(total, pool.workers().len());
+    assert!(started.elapsed() < Duration::from_secs(60));
+}
@@ -0,0 +1,43 @@
+use tokio_stream::StreamExt;
+
+use rust_patterns_for_lifetime_management::config::{Config, Environment};
+use rust_patterns_for_lifetime_management::shared::SharedConfig;
+use rust_patterns_for_lifetime_management::worker::WorkerPool;
+
+#[tokio::main]
+async fn main() {
+    // A subscribed receiver is woken up by `reload` and handed the new
+    // config itself...
+    let pool = WorkerPool::new(Config::for_environment(Environment::Prod), 2);
+    let mut reloads = pool.subscribe();
+    assert_eq!(reloads.borrow().path, "/etc/nginx/nginx.conf");
+    pool.reload(Config::try_from("/etc/nginx/reloaded.conf").unwrap());
+    reloads.changed().await.unwrap();
+    assert_eq!(reloads.borrow_and_update().path, "/etc/nginx/reloaded.conf");
+
+    // ...which an unannounced edit of the shared config does not change.
+    pool.config_mut().path = String::from("/etc/nginx/edited.conf");
+    assert_eq!(reloads.borrow().path, "/etc/nginx/reloaded.conf");
+    assert!(!reloads.has_changed().unwrap());
+
+    // It learns that no more updates are coming once the pool, which
+    // owns the sender, is dropped.
+    drop(pool);
+    assert!(reloads.changed().await.is_err());
+
+    // A `SharedConfig` stream yields an owned clone after each write.
+    let shared = SharedConfig::new(Config::for_environment(Environment::Prod));
+    let mut changes = shared.changes();
+    let mut seen = Vec::new();
+    for path in ["/etc/nginx/first.conf", "/etc/nginx/second.conf"] {
+        *shared.write() = Config::try_from(path).unwrap();
+        seen.push(changes.next().await.unwrap());
+    }
+    assert_eq!(seen[0].path, "/etc/nginx/first.conf");
+    assert_eq!(seen[1].path, "/etc/nginx/second.conf");
+
+    // The stream does not keep the config alive, so it ends with the
+    // last handle.
+    drop(shared);
+    assert!(changes.next().await.is_none());
+}
@@ -32,6 +32,7 @@ fn main() {
     // Thanks to the use of `Arc<Config>`, we are only storing 1 copy
     // of `Config` on the heap, and passing a counted reference to each
     // `Worker` by calling `clone()` on the `Arc<Config>` object.
+    #[allow(clippy::manual_repeat_n)] // As in the post, which predates `repeat_n`.
     let workers: Vec<Worker> = iter::repeat(Worker {
         config: config.clone(),
     })
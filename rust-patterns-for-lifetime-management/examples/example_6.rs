@@ -0,0 +1,102 @@
+use std::panic;
+
+use rust_patterns_for_lifetime_management::config::Config;
+use rust_patterns_for_lifetime_management::versioned::VersionHistory;
+
+fn main() {
+    // `history` owns every version we push into it.
+    let mut history = VersionHistory::new();
+    assert!(history.is_empty());
+
+    // Each `Config` is moved into the history, which assigns the next
+    // version number on our behalf.
+    for i in 1..=5 {
+        let path = format!("/etc/nginx/nginx.{i}.conf");
+        history.push(Config::try_from(path.as_str()).unwrap());
+    }
+
+    // Looking a version up only borrows it from the history.
+    let version_3 = history.get(3).unwrap();
+
+    assert_eq!(history.len(), 5);
+    assert_eq!(version_3.version, 3);
+    // `Versioned` derefs to its inner `Config`, so `.path` reads through.
+    assert_eq!(version_3.path, "/etc/nginx/nginx.3.conf");
+    assert_eq!(history.latest().unwrap().version, 5);
+    assert!(history.get(6).is_none());
+
+    // A bounded history drops the oldest versions but keeps counting.
+    let mut bounded = VersionHistory::with_capacity(2);
+    for i in 1..=4 {
+        bounded.push(format!("/etc/nginx/nginx.{i}.conf"));
+    }
+    assert_eq!(bounded.len(), 2);
+    assert!(bounded.get(1).is_none());
+    assert!(bounded.get(2).is_none());
+    assert_eq!(bounded.get(3).unwrap().obj, "/etc/nginx/nginx.3.conf");
+    assert_eq!(bounded.get(4).unwrap().obj, "/etc/nginx/nginx.4.conf");
+
+    // `retain_latest` prunes once, without bounding later pushes.
+    let mut pruned: VersionHistory<String> = (1..=5)
+        .map(|i| format!("/etc/nginx/nginx.{i}.conf"))
+        .collect();
+    pruned.retain_latest(2);
+    assert_eq!(pruned.len(), 2);
+    assert!(pruned.get(3).is_none());
+    assert_eq!(pruned.get(4).unwrap().obj, "/etc/nginx/nginx.4.conf");
+    assert_eq!(pruned.get(5).unwrap().obj, "/etc/nginx/nginx.5.conf");
+    pruned.push(String::from("/etc/nginx/nginx.6.conf"));
+    assert_eq!(pruned.len(), 3);
+    assert_eq!(pruned.latest().unwrap().version, 6);
+
+    // Collecting moves each config into the history in iteration order.
+    let mut collected: VersionHistory<Config> = (1..=3)
+        .map(|i| Config::try_from(format!("/etc/nginx/nginx.{i}.conf").as_str()).unwrap())
+        .collect();
+    for i in 1..=3 {
+        let path = format!("/etc/nginx/nginx.{i}.conf");
+        assert_eq!(collected.get(i).unwrap().path, path);
+    }
+    collected.extend([Config::default()]);
+    assert_eq!(collected.latest().unwrap().version, 4);
+
+    // Indexing goes by version number and can edit an entry in place.
+    let mut indexed: VersionHistory<String> =
+        ["a", "b", "c"].map(String::from).into_iter().collect();
+    assert_eq!(indexed[2].obj, "b");
+    indexed[2].obj.push('!');
+    assert_eq!(indexed.get(2).unwrap().obj, "b!");
+
+    // An unknown version panics, like indexing past the end of a slice.
+    panic::set_hook(Box::new(|_| {}));
+    let missing = panic::catch_unwind(|| indexed[9].version).unwrap_err();
+    let _ = panic::take_hook();
+    assert_eq!(
+        missing.downcast_ref::<String>().unwrap(),
+        "version 9 is not in the history"
+    );
+
+    // `zip` pairs versions both lineages have, regardless of content type.
+    let mut notes = VersionHistory::new();
+    notes.extend([String::from("initial"), String::from("moved")]);
+    let pairs: Vec<(&str, &str)> = collected
+        .zip(&notes)
+        .map(|(config, note)| (config.path.as_str(), note.obj.as_str()))
+        .collect();
+    assert_eq!(
+        pairs,
+        [
+            ("/etc/nginx/nginx.1.conf", "initial"),
+            ("/etc/nginx/nginx.2.conf", "moved")
+        ]
+    );
+
+    // Versions missing from either side are stepped over, whichever side
+    // runs ahead.
+    let mut recent = VersionHistory::with_capacity(2);
+    recent.extend(["a", "b", "c", "d"]);
+    let common: Vec<u32> = history.zip(&recent).map(|(v, _)| v.version).collect();
+    assert_eq!(common, [3, 4]);
+    let common: Vec<u32> = recent.zip(&history).map(|(v, _)| v.version).collect();
+    assert_eq!(common, [3, 4]);
+}
@@ -0,0 +1,111 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use rust_patterns_for_lifetime_management::config::{Config, ConfigError, Format};
+use rust_patterns_for_lifetime_management::versioned::{
+    next_version, version, VersionHistory, Versioned,
+};
+
+const CAPACITY: usize = 1024;
+
+fn main() {
+    let versioned_config = version(Config {
+        path: String::from("/etc/nginx/nginx.conf"),
+        very_long_vector: Vec::with_capacity(CAPACITY),
+    });
+
+    // Serializing only borrows `versioned_config`; the JSON string is a
+    // brand new owned value.
+    let json = serde_json::to_string(&versioned_config).unwrap();
+
+    // Deserializing builds an entirely separate `Versioned<Config>` that
+    // shares nothing with the original.
+    let restored: Versioned<Config> = serde_json::from_str(&json).unwrap();
+
+    // The reserved capacity is not part of the serialized form, so only
+    // the `version` and `path` are expected to survive the round trip.
+    assert_eq!(restored.version, versioned_config.version);
+    assert_eq!(restored.path, versioned_config.path);
+    assert_eq!(restored, versioned_config);
+
+    // Only `version` and `obj` are written; the timestamp is not.
+    let fields: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let mut keys: Vec<&String> = fields.as_object().unwrap().keys().collect();
+    keys.sort();
+    assert_eq!(keys, ["obj", "version"]);
+
+    // A JSON document with a `path` key can be read from any reader.
+    let from_json = Config::from_reader(&br#"{"path": "/etc/nginx/nginx.conf"}"#[..], Format::Json);
+    assert_eq!(from_json.unwrap().path, "/etc/nginx/nginx.conf");
+    assert!(matches!(
+        Config::from_reader(&b"{}"[..], Format::Json),
+        Err(ConfigError::MissingPath)
+    ));
+
+    // A checkpoint is written to a temporary file and renamed into place,
+    // so `from_checkpoint` only ever sees a complete version.
+    let dir = env::temp_dir().join(format!("example_7-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let checkpoint = dir.join("nginx.json");
+
+    let next = next_version(
+        Config {
+            path: String::from("/etc/nginx/reloaded.conf"),
+            very_long_vector: Vec::new(),
+        },
+        &versioned_config,
+    );
+    versioned_config.checkpoint(&checkpoint).unwrap();
+    next.checkpoint(&checkpoint).unwrap();
+
+    let loaded = Versioned::from_checkpoint(&checkpoint).unwrap();
+    assert_eq!(loaded.version, 2);
+    assert_eq!(loaded.path, "/etc/nginx/reloaded.conf");
+    // Only the checkpoint itself is left behind; every temporary file
+    // was renamed away.
+    let leftovers: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(leftovers, ["nginx.json"]);
+
+    // Renaming over a directory fails, and the temporary file written for
+    // it is removed rather than left next to the target.
+    let blocked = dir.join("blocked.json");
+    fs::create_dir_all(&blocked).unwrap();
+    assert!(matches!(
+        next.checkpoint(&blocked),
+        Err(ConfigError::UnwritableFile { .. })
+    ));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+    fs::remove_dir(&blocked).unwrap();
+
+    assert!(matches!(
+        Versioned::from_checkpoint(&dir.join("missing.json")),
+        Err(ConfigError::MissingFile { .. })
+    ));
+
+    // A whole history is saved as one JSON array and loaded back with the
+    // same version numbers, even after older versions were evicted.
+    let mut history = VersionHistory::with_capacity(3);
+    for i in 1..=4 {
+        history.push(Config {
+            path: format!("/etc/nginx/nginx.{i}.conf"),
+            very_long_vector: Vec::new(),
+        });
+    }
+    let saved = dir.join("history.json");
+    history.save(&saved).unwrap();
+
+    let mut loaded = VersionHistory::load(&saved).unwrap();
+    assert_eq!(loaded.len(), 3);
+    for i in 2..=4 {
+        let path = format!("/etc/nginx/nginx.{i}.conf");
+        assert_eq!(loaded.get(i).unwrap().path, path);
+    }
+    loaded.push(Config::default());
+    assert_eq!(loaded.latest().unwrap().version, 5);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,496 @@
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::os::unix;
+use std::os::unix::ffi::OsStrExt;
+use std::process;
+use std::sync::Arc;
+
+use rust_patterns_for_lifetime_management::config::intern::PathStore;
+use rust_patterns_for_lifetime_management::config::validate::{Rule, Validator};
+use rust_patterns_for_lifetime_management::config::{
+    self, is_valid_config, is_valid_config_cow, Config, ConfigBuilder, ConfigError, Environment,
+};
+use rust_patterns_for_lifetime_management::config_diff::{self, Change};
+use rust_patterns_for_lifetime_management::versioned::{next_version, version};
+
+fn main() {
+    let empty = Config {
+        path: String::new(),
+        very_long_vector: Vec::new(),
+    };
+    let relative = Config {
+        path: String::from("nginx/nginx.conf"),
+        very_long_vector: Vec::new(),
+    };
+    let absolute = Config {
+        path: String::from("/etc/nginx/nginx.conf"),
+        very_long_vector: Vec::new(),
+    };
+
+    // `validate` only borrows each config, so `main` keeps ownership of
+    // all three for the rest of the program.
+    assert!(matches!(empty.validate(), Err(ConfigError::EmptyPath)));
+    assert!(matches!(
+        relative.validate(),
+        Err(ConfigError::NonAbsolutePath(path)) if path == "nginx/nginx.conf"
+    ));
+    assert!(absolute.validate().is_ok());
+
+    // An empty path is both empty and not absolute; `validate_all` reports
+    // both problems where `validate` stops at the first.
+    assert!(matches!(
+        empty.validate_all().unwrap_err().as_slice(),
+        [ConfigError::EmptyPath, ConfigError::NonAbsolutePath(path)] if path.is_empty()
+    ));
+    assert!(absolute.validate_all().is_ok());
+
+    assert!(!is_valid_config(&empty));
+    assert!(!is_valid_config(&relative));
+    assert!(is_valid_config(&absolute));
+
+    // Custom rules run alongside the standard ones.
+    struct ConfExtension;
+
+    impl Rule for ConfExtension {
+        fn check(&self, config: &Config) -> Result<(), ConfigError> {
+            if !config.path.ends_with(".conf") {
+                return Err(ConfigError::RuleFailed(format!(
+                    "`{}` does not end in .conf",
+                    config.path
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    let validator = Validator::standard()
+        .rule(ConfExtension)
+        .rule(|config: &Config| match config.very_long_vector.len() {
+            0..=10 => Ok(()),
+            _ => Err(ConfigError::RuleFailed(String::from("too many items"))),
+        });
+    assert!(validator.check(&absolute).is_ok());
+
+    let toml = Config {
+        path: String::from("/etc/nginx/nginx.toml"),
+        very_long_vector: Vec::new(),
+    };
+    assert!(toml.validate_all().is_ok());
+    assert!(matches!(
+        validator.check(&toml).unwrap_err().as_slice(),
+        [ConfigError::RuleFailed(reason)] if reason == "`/etc/nginx/nginx.toml` does not end in .conf"
+    ));
+
+    // `is_valid_config_cow` takes a borrow or an owned config alike; the
+    // borrow leaves `absolute` usable, while the owned clone of `relative`
+    // is moved in and dropped.
+    assert!(is_valid_config_cow(&absolute));
+    assert!(!is_valid_config_cow(relative.clone()));
+
+    // The builder owns its fields until `build` moves them into the
+    // new `Config`.
+    let built = ConfigBuilder::new()
+        .path("/etc/nginx/nginx.conf")
+        .reserve(128)
+        .build();
+    assert_eq!(built.path, "/etc/nginx/nginx.conf");
+    assert!(built.very_long_vector.capacity() >= 128);
+
+    let unnamed = ConfigBuilder::new().build();
+    assert!(unnamed.path.is_empty());
+    assert_eq!(unnamed, Config::default());
+
+    // Equality ignores how much capacity each vector reserved.
+    assert_eq!(
+        ConfigBuilder::new()
+            .path("/etc/nginx/nginx.conf")
+            .reserve(8)
+            .build(),
+        ConfigBuilder::new()
+            .path("/etc/nginx/nginx.conf")
+            .reserve(64)
+            .build()
+    );
+    assert_ne!(
+        ConfigBuilder::new().path("/etc/nginx/nginx.conf").build(),
+        ConfigBuilder::new()
+            .path("/etc/apache2/apache2.conf")
+            .build()
+    );
+
+    assert!(Config::default().path.is_empty());
+    assert!(!is_valid_config(&Config::default()));
+
+    // `merge` consumes both the base and the override.
+    let base = ConfigBuilder::new().path("/etc/nginx/nginx.conf").build();
+    let merged = base.merge(
+        ConfigBuilder::new()
+            .path("/etc/nginx/staging.conf")
+            .reserve(64)
+            .build(),
+    );
+    assert_eq!(merged.path, "/etc/nginx/staging.conf");
+    assert!(merged.very_long_vector.capacity() >= 64);
+
+    // An override without a `path` keeps the base path.
+    let merged = merged.merge(ConfigBuilder::new().build());
+    assert_eq!(merged.path, "/etc/nginx/staging.conf");
+    assert!(merged.very_long_vector.capacity() >= 64);
+
+    // `expand_env` consumes each config and hands back the expanded one.
+    env::set_var("EXAMPLE_8_PREFIX", "/etc/nginx");
+    env::remove_var("EXAMPLE_8_UNSET");
+
+    let expanded = ConfigBuilder::new()
+        .path("${EXAMPLE_8_PREFIX}/nginx.conf")
+        .build()
+        .expand_env();
+    assert_eq!(expanded.path, "/etc/nginx/nginx.conf");
+
+    let expanded = ConfigBuilder::new()
+        .path("$EXAMPLE_8_PREFIX/sites/$EXAMPLE_8_UNSET.conf")
+        .build()
+        .expand_env();
+    assert_eq!(expanded.path, "/etc/nginx/sites/$EXAMPLE_8_UNSET.conf");
+
+    let expanded = ConfigBuilder::new()
+        .path("/etc/nginx/nginx.conf")
+        .build()
+        .expand_env();
+    assert_eq!(expanded.path, "/etc/nginx/nginx.conf");
+
+    // `diff` borrows both sides, so both configs remain usable afterwards.
+    let before = ConfigBuilder::new().path("/etc/nginx/nginx.conf").build();
+    assert!(before.diff(&before.clone()).is_empty());
+
+    let moved = ConfigBuilder::new().path("/etc/nginx/moved.conf").build();
+    let diff = before.diff(&moved);
+    assert_eq!(
+        diff.path,
+        Some(Change {
+            old: String::from("/etc/nginx/nginx.conf"),
+            new: String::from("/etc/nginx/moved.conf"),
+        })
+    );
+    assert_eq!(diff.len, None);
+
+    let grown = Config {
+        path: String::from("/etc/nginx/moved.conf"),
+        very_long_vector: vec![String::from("worker_processes 4;")],
+    };
+    let diff = before.diff(&grown);
+    assert!(diff.path.is_some());
+    assert_eq!(diff.len, Some(Change { old: 0, new: 1 }));
+    assert!(!diff.is_empty());
+
+    // `patch` replays a diff onto a clone of the original. Only lengths are
+    // recorded, so an item added on the far side comes back empty.
+    let patched = config_diff::patch(before.clone(), &diff);
+    assert_eq!(patched.path, grown.path);
+    assert_eq!(patched.very_long_vector, [String::new()]);
+
+    let shrunk = Config {
+        path: String::from("/etc/nginx/nginx.conf"),
+        very_long_vector: Vec::new(),
+    };
+    let diff = grown.diff(&shrunk);
+    assert_eq!(config_diff::patch(grown.clone(), &diff), shrunk);
+    assert_eq!(
+        config_diff::patch(grown.clone(), &grown.diff(&grown)),
+        grown
+    );
+
+    // `validate_exists` also checks the filesystem.
+    let dir = env::temp_dir().join(format!("example_8-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("nginx.conf");
+    fs::write(&file, "worker_processes 4;\n").unwrap();
+
+    let on_disk = ConfigBuilder::new().path(file.to_str().unwrap()).build();
+    assert!(on_disk.validate_exists().is_ok());
+
+    let directory = ConfigBuilder::new().path(dir.to_str().unwrap()).build();
+    assert!(matches!(
+        directory.validate_exists(),
+        Err(ConfigError::NotAFile(path)) if path == directory.path
+    ));
+
+    let bogus = ConfigBuilder::new().path("/does/not/exist.conf").build();
+    assert!(matches!(
+        bogus.validate_exists(),
+        Err(ConfigError::NotFound(path)) if path == "/does/not/exist.conf"
+    ));
+
+    // `?` converts into `io::Error` for callers returning `io::Result`.
+    // Validation failures become `InvalidData`...
+    assert_eq!(
+        exists(&empty).unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    // ...while errors from the filesystem keep their original kind.
+    assert_eq!(exists(&bogus).unwrap_err().kind(), io::ErrorKind::NotFound);
+    let unreadable = fs::read_to_string(&dir).unwrap_err();
+    let kind = unreadable.kind();
+    let err = io::Error::from(ConfigError::UnreadableFile {
+        path: dir.display().to_string(),
+        source: unreadable,
+    });
+    assert_eq!(err.kind(), kind);
+    let denied = io::Error::from(ConfigError::Io(io::ErrorKind::PermissionDenied.into()));
+    assert_eq!(denied.kind(), io::ErrorKind::PermissionDenied);
+
+    // `canonicalize_path` normalizes the path against the filesystem.
+    let canonical_file = fs::canonicalize(&file).unwrap().display().to_string();
+    let redundant = ConfigBuilder::new()
+        .path(format!("{}//./nginx.conf", dir.display()))
+        .build()
+        .canonicalize_path()
+        .unwrap();
+    assert_eq!(redundant.path, canonical_file);
+
+    let cwd = env::current_dir().unwrap();
+    env::set_current_dir(&dir).unwrap();
+    let relative = ConfigBuilder::new()
+        .path("nginx.conf")
+        .build()
+        .canonicalize_path()
+        .unwrap();
+    assert_eq!(relative.path, canonical_file);
+    env::set_current_dir(cwd).unwrap();
+
+    assert!(matches!(
+        bogus.canonicalize_path(),
+        Err(ConfigError::NotFound(path)) if path == "/does/not/exist.conf"
+    ));
+
+    // A symlink to a file whose name is not Unicode cannot be stored in
+    // `path` without losing bytes, so it is refused.
+    let non_unicode = dir.join(OsStr::from_bytes(b"nginx.\xff.conf"));
+    fs::write(&non_unicode, "worker_processes 4;\n").unwrap();
+    let link = dir.join("link.conf");
+    unix::fs::symlink(&non_unicode, &link).unwrap();
+    assert!(matches!(
+        ConfigBuilder::new()
+            .path(link.to_str().unwrap())
+            .build()
+            .canonicalize_path(),
+        Err(ConfigError::NonUnicodePath(path)) if path.ends_with("nginx.\u{fffd}.conf")
+    ));
+
+    // `normalize` expands `$TMPDIR` before resolving the path on disk.
+    env::set_var("TMPDIR", env::temp_dir());
+    let normalized = ConfigBuilder::new()
+        .path(format!(
+            "$TMPDIR/{}/./nginx.conf",
+            dir.file_name().unwrap().to_str().unwrap()
+        ))
+        .build()
+        .normalize()
+        .unwrap();
+    assert_eq!(normalized.path, canonical_file);
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    // Each environment has its own baseline path.
+    let dev = Config::for_environment("dev".parse().unwrap());
+    let staging = Config::for_environment("Staging".parse().unwrap());
+    let prod = Config::for_environment("PROD".parse().unwrap());
+    assert_eq!(dev.path, "/etc/nginx/nginx.dev.conf");
+    assert_eq!(staging.path, "/etc/nginx/nginx.staging.conf");
+    assert_eq!(prod.path, "/etc/nginx/nginx.conf");
+    assert!(matches!(
+        "qa".parse::<Environment>(),
+        Err(ConfigError::UnknownEnvironment(env)) if env == "qa"
+    ));
+
+    // `path_components` borrows its segments from the config, which has
+    // to outlive them.
+    let trailing = ConfigBuilder::new().path("/etc/nginx/").build();
+    let root = ConfigBuilder::new().path("/").build();
+    assert_eq!(absolute.path_components(), ["etc", "nginx", "nginx.conf"]);
+    assert_eq!(trailing.path_components(), ["etc", "nginx"]);
+    assert!(root.path_components().is_empty());
+
+    // `sanitize_path` only rewrites the string.
+    let sanitize = |path: &str| ConfigBuilder::new().path(path).build().sanitize_path().path;
+    assert_eq!(sanitize("/etc//nginx/"), "/etc/nginx");
+    assert_eq!(sanitize("/"), "/");
+    assert_eq!(sanitize("///"), "/");
+    assert_eq!(
+        sanitize("  /etc/nginx/nginx.conf \n"),
+        "/etc/nginx/nginx.conf"
+    );
+
+    // Interned paths are shared between configs instead of copied.
+    let first = Config::with_interned_path("/etc/nginx/nginx.conf");
+    let second = Config::with_interned_path("/etc/nginx/nginx.conf");
+    let other = Config::with_interned_path("/etc/apache2/apache2.conf");
+    assert!(Arc::ptr_eq(first.path_arc(), second.path_arc()));
+    assert!(Arc::ptr_eq(first.path_arc(), first.clone().path_arc()));
+    assert!(!Arc::ptr_eq(first.path_arc(), other.path_arc()));
+    assert_eq!(second.path(), "/etc/nginx/nginx.conf");
+    assert_eq!(first.into_config().path, "/etc/nginx/nginx.conf");
+
+    // The store keeps a path until it is cleared, and clearing only evicts
+    // paths no config holds any more.
+    let store = PathStore::new();
+    let kept = store.intern("/etc/nginx/nginx.conf");
+    drop(store.intern("/etc/apache2/apache2.conf"));
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.clear_unused(), 1);
+    assert_eq!(store.len(), 1);
+    assert!(Arc::ptr_eq(&kept, &store.intern("/etc/nginx/nginx.conf")));
+
+    // The global store behind `with_interned_path` is cleared the same way.
+    drop(other);
+    assert!(PathStore::global().clear_unused() >= 1);
+    assert!(Arc::ptr_eq(
+        second.path_arc(),
+        Config::with_interned_path("/etc/nginx/nginx.conf").path_arc()
+    ));
+
+    // `try_from` copies a borrowed path string into a new config.
+    let parsed = Config::try_from("/etc/nginx/nginx.conf").unwrap();
+    assert_eq!(parsed.path, "/etc/nginx/nginx.conf");
+    assert!(parsed.very_long_vector.is_empty());
+    assert!(matches!(Config::try_from(""), Err(ConfigError::EmptyPath)));
+
+    // Each build profile has its own baked-in baseline.
+    let debug = config::profile::debug();
+    let release = config::profile::release();
+    assert_ne!(debug.path, release.path);
+    assert!(release.very_long_vector.capacity() >= config::profile::RELEASE_CAPACITY);
+
+    let current = config::profile::current();
+    #[cfg(debug_assertions)]
+    assert_eq!(current, debug);
+    #[cfg(not(debug_assertions))]
+    assert_eq!(current, release);
+
+    // `set_path` mutates through a borrow, but only with a non-empty path.
+    let mut config = Config::for_environment(Environment::Prod);
+    assert!(config.set_path("/etc/nginx/next.conf").is_ok());
+    assert_eq!(config.path, "/etc/nginx/next.conf");
+    assert!(matches!(config.set_path(""), Err(ConfigError::EmptyPath)));
+    assert_eq!(config.path, "/etc/nginx/next.conf");
+
+    // The registry owns named baselines and hands out clones of them.
+    config::defaults::register("nginx", Config::for_environment(Environment::Prod));
+    let mut baseline = config::defaults::get("nginx").unwrap();
+    assert_eq!(baseline.path, "/etc/nginx/nginx.conf");
+    baseline.path.push_str(".bak");
+    assert_eq!(
+        config::defaults::get("nginx").unwrap().path,
+        "/etc/nginx/nginx.conf"
+    );
+    assert!(config::defaults::get("apache").is_none());
+
+    // `apply` moves the config through a closure and validates the result.
+    let applied = config
+        .apply(|config| config.path = String::from("/etc/nginx/applied.conf"))
+        .unwrap();
+    assert_eq!(applied.path, "/etc/nginx/applied.conf");
+
+    // A failed validation still returns the config, so it is not lost.
+    let (cleared, err) = applied.apply(|config| config.path.clear()).unwrap_err();
+    assert!(matches!(err, ConfigError::EmptyPath));
+    assert!(cleared.path.is_empty());
+
+    // `with_reserved` reports an impossible allocation instead of
+    // aborting the process.
+    let reserved = Config::with_reserved("/etc/nginx/nginx.conf", 1024).unwrap();
+    assert!(reserved.very_long_vector.capacity() >= 1024);
+    assert!(matches!(
+        Config::with_reserved("/etc/nginx/nginx.conf", usize::MAX),
+        Err(ConfigError::Alloc(_))
+    ));
+
+    // `with_items` fills the vector with real entries, which equality and
+    // diffs then take into account.
+    let items = [
+        "worker_processes 4;",
+        "pid /run/nginx.pid;",
+        "user www-data;",
+    ]
+    .map(String::from)
+    .to_vec();
+    let filled = Config::with_items("/etc/nginx/nginx.conf", items.clone());
+    assert_eq!(filled.items(), &items[..]);
+    assert_ne!(filled, reserved);
+    let mut trimmed = filled.clone();
+    trimmed.very_long_vector.pop();
+    assert_ne!(filled, trimmed);
+    assert!(!filled.diff(&trimmed).is_empty());
+
+    // `diff_from` answers "what changed between v1 and v2".
+    let v1 = version(Config::for_environment(Environment::Staging));
+    let v2 = next_version(Config::for_environment(Environment::Prod), &v1);
+    let (from, to, diff) = v2.diff_from(&v1);
+    assert_eq!((from, to), (1, 2));
+    assert_eq!(
+        diff.path,
+        Some(Change {
+            old: String::from("/etc/nginx/nginx.staging.conf"),
+            new: String::from("/etc/nginx/nginx.conf"),
+        })
+    );
+    assert_eq!(diff.len, None);
+
+    // Cloning gets more expensive as the vector fills up.
+    let mut growing = Config::for_environment(Environment::Prod);
+    let empty_cost = growing.clone_cost();
+    growing
+        .very_long_vector
+        .push(String::from("worker_processes 4;"));
+    assert!(growing.clone_cost() > empty_cost);
+
+    // A builder derived from an existing config only needs the fields
+    // that change.
+    let base = ConfigBuilder::new()
+        .path("/etc/nginx/nginx.conf")
+        .reserve(64)
+        .build();
+    let tweaked = base.into_builder().path("/etc/nginx/tweaked.conf").build();
+    assert_eq!(tweaked.path, "/etc/nginx/tweaked.conf");
+    assert!(tweaked.very_long_vector.capacity() >= 64);
+
+    // Every field can also come from prefixed environment variables.
+    env::set_var("EXAMPLE_8_PATH", "/etc/nginx/nginx.conf");
+    env::set_var("EXAMPLE_8_CAPACITY", "32");
+    let from_env = config::env::from_prefix("EXAMPLE_8").unwrap();
+    assert_eq!(from_env.path, "/etc/nginx/nginx.conf");
+    assert!(from_env.very_long_vector.capacity() >= 32);
+
+    env::set_var("EXAMPLE_8_CAPACITY", "lots");
+    assert!(matches!(
+        config::env::from_prefix("EXAMPLE_8"),
+        Err(ConfigError::InvalidCapacity(value)) if value == "lots"
+    ));
+
+    assert!(matches!(
+        config::env::from_prefix("EXAMPLE_8_MISSING"),
+        Err(ConfigError::MissingEnvVar(var)) if var == "EXAMPLE_8_MISSING_PATH"
+    ));
+
+    // A variable that is set to something other than Unicode is reported
+    // as such, not mistaken for an unset one.
+    env::set_var("EXAMPLE_8_CAPACITY", OsStr::from_bytes(b"3\xff"));
+    assert!(matches!(
+        config::env::from_prefix("EXAMPLE_8"),
+        Err(ConfigError::InvalidCapacity(value)) if value == "3\u{fffd}"
+    ));
+
+    env::set_var("EXAMPLE_8_PATH", OsStr::from_bytes(b"/etc/nginx/\xff.conf"));
+    assert!(matches!(
+        config::env::from_prefix("EXAMPLE_8"),
+        Err(ConfigError::NonUnicodeEnvVar(var)) if var == "EXAMPLE_8_PATH"
+    ));
+}
+
+/// Stands in for IO-heavy code that only knows about `io::Result`.
+fn exists(config: &Config) -> io::Result<()> {
+    config.validate_exists()?;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::env;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::process;
+
+use rust_patterns_for_lifetime_management::config::{layered, Config, ConfigError, Format};
+
+fn main() {
+    let dir = env::temp_dir().join(format!("example_9-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let nginx = dir.join("nginx.toml");
+    fs::write(&nginx, "path = \"/etc/nginx/nginx.conf\"\n").unwrap();
+
+    let no_path = dir.join("no_path.toml");
+    fs::write(&no_path, "other = 1\n").unwrap();
+
+    // `from_toml_path` only borrows the file path, so `main` can keep
+    // using `nginx` after the config is loaded.
+    let config = Config::from_toml_path(&nginx).unwrap();
+    assert_eq!(config.path, "/etc/nginx/nginx.conf");
+    assert!(nginx.exists());
+
+    assert!(matches!(
+        Config::from_toml_path(&no_path),
+        Err(ConfigError::MissingPath)
+    ));
+    assert!(matches!(
+        Config::from_toml_path(dir.join("missing.toml")),
+        Err(ConfigError::MissingFile { .. })
+    ));
+    // The underlying `io::Error` is kept as the source of the failure.
+    let missing = Config::from_toml_path(dir.join("missing.toml")).unwrap_err();
+    assert!(missing.source().is_some());
+    // Reading a directory as a file fails with something other than
+    // `NotFound`.
+    assert!(matches!(
+        Config::from_toml_path(&dir),
+        Err(ConfigError::UnreadableFile { .. })
+    ));
+
+    // `from_reader` parses from anything readable, such as bytes that
+    // never touched the filesystem.
+    let buffer: &[u8] = b"path = \"/etc/nginx/embedded.conf\"\n";
+    let embedded = Config::from_reader(buffer, Format::Toml).unwrap();
+    assert_eq!(embedded.path, "/etc/nginx/embedded.conf");
+    assert!(matches!(
+        Config::from_reader(&b"path = "[..], Format::Toml),
+        Err(ConfigError::InvalidToml(_))
+    ));
+
+    // Layers: defaults, then the file, then the environment.
+    env::remove_var(layered::PATH_VAR);
+    assert_eq!(
+        layered::load_from(dir.join("missing.toml")).unwrap(),
+        Config::default()
+    );
+    assert_eq!(
+        layered::load_from(&nginx).unwrap().path,
+        "/etc/nginx/nginx.conf"
+    );
+    assert!(matches!(
+        layered::load_from(&no_path),
+        Err(ConfigError::MissingPath)
+    ));
+
+    env::set_var(layered::PATH_VAR, "/etc/nginx/from_env.conf");
+    assert_eq!(
+        layered::load_from(&nginx).unwrap().path,
+        "/etc/nginx/from_env.conf"
+    );
+
+    // A variable that is set but unreadable is an error, rather than
+    // quietly letting the file win.
+    env::set_var(
+        layered::PATH_VAR,
+        OsStr::from_bytes(b"/etc/nginx/\xff.conf"),
+    );
+    assert!(matches!(
+        layered::load_from(&nginx),
+        Err(ConfigError::NonUnicodeEnvVar(var)) if var == layered::PATH_VAR
+    ));
+    env::remove_var(layered::PATH_VAR);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
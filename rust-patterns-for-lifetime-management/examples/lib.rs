@@ -0,0 +1,24 @@
+//! Shared types for the examples that build on the patterns above.
+//!
+//! `example_1` through `example_5` are kept self-contained so that they
+//! match the code listings in the post. Later examples pull their
+//! `Config` and `Versioned` types from here instead.
+//!
+//! Everything except [`versioned::Versioned`] sits behind the default
+//! `std` feature. Without it the crate is `no_std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod config_diff;
+#[cfg(feature = "watch")]
+pub mod config_watch;
+#[cfg(feature = "std")]
+pub mod scope;
+#[cfg(feature = "std")]
+pub mod shared;
+pub mod versioned;
+#[cfg(feature = "std")]
+pub mod worker;
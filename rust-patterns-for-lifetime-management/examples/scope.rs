@@ -0,0 +1,33 @@
+use std::mem;
+
+use crate::config::Config;
+use crate::shared::SharedConfig;
+
+/// Restores the config it displaced when it goes out of scope.
+///
+/// The guard keeps its own handle to the `SharedConfig`, so it does not
+/// borrow the handle it was created from.
+#[must_use = "the original config is restored as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct ConfigGuard {
+    shared: SharedConfig,
+    original: Option<Config>,
+}
+
+/// Swaps `temp` into `shared` until the returned guard is dropped.
+pub fn override_config(shared: &SharedConfig, temp: Config) -> ConfigGuard {
+    let original = mem::replace(&mut *shared.write(), temp);
+
+    ConfigGuard {
+        shared: shared.clone_handle(),
+        original: Some(original),
+    }
+}
+
+impl Drop for ConfigGuard {
+    fn drop(&mut self) {
+        if let Some(original) = self.original.take() {
+            *self.shared.write() = original;
+        }
+    }
+}
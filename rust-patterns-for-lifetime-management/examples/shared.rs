@@ -0,0 +1,108 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
+
+use tokio::sync::watch;
+use tokio_stream::wrappers::WatchStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::config::Config;
+
+/// A `Config` that can be mutated at runtime by any holder of a handle.
+///
+/// Like the `Arc<Config>` in `example_5`, there is only ever one copy of
+/// the `Config` on the heap. The `RwLock` adds the ability to mutate it:
+/// any number of readers may hold it at once, but a writer gets exclusive
+/// access.
+#[derive(Clone, Debug)]
+pub struct SharedConfig(Arc<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+    config: RwLock<Config>,
+    /// Ticks once per write. Only the handles own it, so it closes when
+    /// the last one is dropped.
+    changes: watch::Sender<()>,
+}
+
+impl SharedConfig {
+    pub fn new(config: Config) -> Self {
+        SharedConfig(Arc::new(Inner {
+            config: RwLock::new(config),
+            changes: watch::channel(()).0,
+        }))
+    }
+
+    /// A poisoned lock only means another holder panicked mid-write;
+    /// `Config` has no invariants that could be left half-updated, so the
+    /// guard is recovered rather than propagating the panic.
+    pub fn read(&self) -> RwLockReadGuard<'_, Config> {
+        self.0.config.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Exclusive access to the config. Streams from
+    /// [`SharedConfig::changes`] are notified once the guard is dropped.
+    pub fn write(&self) -> ConfigWriteGuard<'_> {
+        ConfigWriteGuard {
+            guard: Some(
+                self.0
+                    .config
+                    .write()
+                    .unwrap_or_else(PoisonError::into_inner),
+            ),
+            changes: &self.0.changes,
+        }
+    }
+
+    /// Hands out another counted reference to the same `Config`. This is
+    /// the same as `clone`, spelled out to make clear nothing is copied.
+    pub fn clone_handle(&self) -> SharedConfig {
+        SharedConfig(Arc::clone(&self.0))
+    }
+
+    /// A stream that yields a clone of the config after every write made
+    /// from then on, for async code that wants to `.await` changes.
+    ///
+    /// The stream only holds a `Weak` reference, so it does not keep the
+    /// config alive: once every handle is dropped, it ends. Writes that
+    /// land close together may be coalesced into one item.
+    pub fn changes(&self) -> impl Stream<Item = Config> {
+        let config: Weak<Inner> = Arc::downgrade(&self.0);
+
+        WatchStream::from_changes(self.0.changes.subscribe()).map_while(move |()| {
+            let inner = config.upgrade()?;
+            let config = inner.config.read().unwrap_or_else(PoisonError::into_inner);
+            Some(config.clone())
+        })
+    }
+}
+
+/// Returned by [`SharedConfig::write`]. Derefs to the locked `Config`.
+/// When dropped, it releases the lock and then notifies
+/// [`SharedConfig::changes`].
+#[derive(Debug)]
+pub struct ConfigWriteGuard<'a> {
+    guard: Option<RwLockWriteGuard<'a, Config>>,
+    changes: &'a watch::Sender<()>,
+}
+
+impl Deref for ConfigWriteGuard<'_> {
+    type Target = Config;
+
+    fn deref(&self) -> &Config {
+        self.guard.as_ref().expect("guard is only taken on drop")
+    }
+}
+
+impl DerefMut for ConfigWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Config {
+        self.guard.as_mut().expect("guard is only taken on drop")
+    }
+}
+
+impl Drop for ConfigWriteGuard<'_> {
+    fn drop(&mut self) {
+        // Unlock first, so that woken streams do not block on the lock.
+        drop(self.guard.take());
+        self.changes.send_replace(());
+    }
+}
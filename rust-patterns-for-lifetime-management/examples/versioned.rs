@@ -0,0 +1,809 @@
+//! `Versioned` itself only needs `core`, so this module also builds with
+//! the `std` feature turned off. Timestamps, [`VersionHistory`] and the
+//! `Config`-specific helpers are only available with `std`.
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
+use core::iter;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+#[cfg(feature = "std")]
+use core::ops::{Index, IndexMut};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "serde")]
+use std::fs;
+#[cfg(feature = "serde")]
+use std::io::{self, Write};
+#[cfg(feature = "serde")]
+use std::path::Path;
+#[cfg(feature = "serde")]
+use std::process;
+#[cfg(feature = "serde")]
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+#[cfg(feature = "serde")]
+use crate::config::ConfigError;
+#[cfg(feature = "std")]
+use crate::config::{is_valid_config, Config};
+
+#[cfg(feature = "compress")]
+pub mod compressed;
+
+/// Serialized as the two fields `version` and `obj`. `created_at` is
+/// not persisted, so a deserialized version is stamped with the time it
+/// was loaded.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Versioned<O> {
+    pub version: u32,
+    pub obj: O,
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "serde", serde(skip, default = "SystemTime::now"))]
+    created_at: SystemTime,
+}
+
+impl<O> Versioned<O> {
+    /// Stamps `obj` with `version` and, with `std`, the current time.
+    fn stamped(obj: O, version: u32) -> Versioned<O> {
+        Versioned {
+            version,
+            obj,
+            #[cfg(feature = "std")]
+            created_at: SystemTime::now(),
+        }
+    }
+
+    /// Builds a version with an explicit creation time, for callers that
+    /// need deterministic timestamps. [`version`] and [`next_version`]
+    /// stamp `SystemTime::now()` instead.
+    #[cfg(feature = "std")]
+    pub fn with_timestamp(obj: O, version: u32, created_at: SystemTime) -> Versioned<O> {
+        Versioned {
+            version,
+            obj,
+            created_at,
+        }
+    }
+
+    /// When this version was saved.
+    #[cfg(feature = "std")]
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    /// Records the current (possibly mutated through `DerefMut`) contents
+    /// as the next version, in place and without a new wrapper.
+    pub fn bump(&mut self) {
+        self.version += 1;
+        #[cfg(feature = "std")]
+        {
+            self.created_at = SystemTime::now();
+        }
+    }
+
+    /// Moves `obj` in as the next version and hands the displaced value
+    /// back, like `std::mem::replace`.
+    pub fn replace(&mut self, obj: O) -> O {
+        let old = mem::replace(&mut self.obj, obj);
+        self.bump();
+        old
+    }
+
+    /// Consumes the wrapper and moves `obj` into `f`, carrying the
+    /// version number (and, with `std`, the timestamp) over to the result unchanged.
+    pub fn map<B>(self, f: impl FnOnce(O) -> B) -> Versioned<B> {
+        Versioned {
+            version: self.version,
+            obj: f(self.obj),
+            #[cfg(feature = "std")]
+            created_at: self.created_at,
+        }
+    }
+
+    /// Like [`Versioned::map`], but for transformations that can fail.
+    /// On error the original `obj` has already been moved into `f`, so
+    /// only the error is returned.
+    pub fn try_map<B, E>(self, f: impl FnOnce(O) -> Result<B, E>) -> Result<Versioned<B>, E> {
+        Ok(Versioned {
+            version: self.version,
+            obj: f(self.obj)?,
+            #[cfg(feature = "std")]
+            created_at: self.created_at,
+        })
+    }
+
+    /// Compares version numbers only, so `O` needs no trait bounds.
+    pub fn is_newer_than(&self, other: &Versioned<O>) -> bool {
+        self.version > other.version
+    }
+
+    /// Keeps whichever of the two has the higher version, favouring `self`
+    /// on a tie. Both are moved in, so neither `obj` is cloned; the other
+    /// one is dropped.
+    pub fn latest_of(self, other: Versioned<O>) -> Versioned<O> {
+        if other.is_newer_than(&self) {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// The same ordering as the [`Ord`] impl, for sorting with `sort_by`
+    /// when `O` is not `Eq`.
+    pub fn by_version(a: &Versioned<O>, b: &Versioned<O>) -> Ordering {
+        a.version.cmp(&b.version)
+    }
+
+    /// Compares version numbers only; the contents may still differ.
+    pub fn is_same_version(&self, other: &Versioned<O>) -> bool {
+        self.version == other.version
+    }
+
+    /// Gives up the version number and moves `obj` back to the caller,
+    /// as in `example_3`.
+    pub fn into_inner(self) -> O {
+        self.obj
+    }
+}
+
+/// A [`Versioned`] that reports every version it moves to. Built with
+/// [`Versioned::audit_log`].
+///
+/// `log` is called with the new version number and a borrow of the new
+/// contents after each [`bump`](VersionedLogged::bump) or
+/// [`replace`](VersionedLogged::replace), so `O` never has to be cloned
+/// for it. Reads go through `Deref`; mutating `obj` is only possible via
+/// [`obj_mut`](VersionedLogged::obj_mut), so a version cannot change
+/// without being logged.
+#[cfg(feature = "std")]
+pub struct VersionedLogged<O> {
+    inner: Versioned<O>,
+    log: AuditLog<O>,
+}
+
+#[cfg(feature = "std")]
+type AuditLog<O> = Box<dyn FnMut(u32, &O)>;
+
+#[cfg(feature = "std")]
+impl<O> Versioned<O> {
+    /// Moves this version into a [`VersionedLogged`] that calls `log` on
+    /// every later version change. The current version is not logged.
+    pub fn audit_log(self, log: impl FnMut(u32, &O) + 'static) -> VersionedLogged<O> {
+        VersionedLogged {
+            inner: self,
+            log: Box::new(log),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<O> VersionedLogged<O> {
+    /// See [`Versioned::bump`].
+    pub fn bump(&mut self) {
+        self.inner.bump();
+        (self.log)(self.inner.version, &self.inner.obj);
+    }
+
+    /// See [`Versioned::replace`].
+    pub fn replace(&mut self, obj: O) -> O {
+        let old = self.inner.replace(obj);
+        (self.log)(self.inner.version, &self.inner.obj);
+        old
+    }
+
+    /// Lends out `obj` for editing in place; follow up with `bump` to
+    /// record the edit.
+    pub fn obj_mut(&mut self) -> &mut O {
+        &mut self.inner.obj
+    }
+
+    /// Drops the callback and moves the plain [`Versioned`] back out.
+    pub fn into_inner(self) -> Versioned<O> {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<O> Deref for VersionedLogged<O> {
+    type Target = Versioned<O>;
+
+    fn deref(&self) -> &Versioned<O> {
+        &self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<O: fmt::Debug> fmt::Debug for VersionedLogged<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersionedLogged")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<O: Clone> Versioned<O> {
+    /// Clones `obj` into a new lineage starting at version 1. The fork and
+    /// the original own separate copies and are versioned independently.
+    pub fn fork(&self) -> Versioned<O> {
+        version(self.obj.clone())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Versioned<Config> {
+    /// Writes this version to `path` as JSON, crash-safely: the JSON goes
+    /// to a uniquely named sibling temporary file first, which is then
+    /// renamed over `path`.
+    /// If the write is interrupted, any previous checkpoint at `path` is
+    /// left as it was.
+    pub fn checkpoint(&self, path: &Path) -> Result<(), ConfigError> {
+        let json = serde_json::to_vec(self).map_err(ConfigError::InvalidJson)?;
+        write_atomically(path, &json)
+    }
+
+    /// Reads back a version written by [`Versioned::checkpoint`]. The
+    /// result owns fresh copies of everything in the file.
+    pub fn from_checkpoint(path: &Path) -> Result<Versioned<Config>, ConfigError> {
+        let json = read_file(path)?;
+        serde_json::from_slice(&json).map_err(ConfigError::InvalidJson)
+    }
+}
+
+/// Writes `contents` to a sibling temporary file and renames it over
+/// `path`. The temporary name carries the process id and a per-process
+/// counter, so concurrent checkpoints of the same `path` never share one;
+/// if any step fails, the temporary file is removed again.
+#[cfg(feature = "serde")]
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), ConfigError> {
+    static NEXT_TMP: AtomicUsize = AtomicUsize::new(0);
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}.{}.tmp",
+        process::id(),
+        NEXT_TMP.fetch_add(1, AtomicOrdering::Relaxed)
+    ));
+    let tmp = Path::new(&tmp);
+
+    let unwritable = |source: io::Error| ConfigError::UnwritableFile {
+        path: path.display().to_string(),
+        source,
+    };
+
+    let written = fs::File::create(tmp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(tmp, path)
+    });
+    if written.is_err() {
+        // Best effort: the original error is the one worth reporting.
+        let _ = fs::remove_file(tmp);
+    }
+    written.map_err(unwritable)
+}
+
+#[cfg(feature = "serde")]
+fn read_file(path: &Path) -> Result<Vec<u8>, ConfigError> {
+    fs::read(path).map_err(|source| match source.kind() {
+        io::ErrorKind::NotFound => ConfigError::MissingFile {
+            path: path.display().to_string(),
+            source,
+        },
+        _ => ConfigError::UnreadableFile {
+            path: path.display().to_string(),
+            source,
+        },
+    })
+}
+
+/// Two versions are equal when their numbers and contents match. When
+/// each was created is metadata and does not take part.
+impl<O: PartialEq> PartialEq for Versioned<O> {
+    fn eq(&self, other: &Versioned<O>) -> bool {
+        self.version == other.version && self.obj == other.obj
+    }
+}
+
+impl<O: Eq> Eq for Versioned<O> {}
+
+/// Versions are ordered chronologically by `version` alone, so `O` does
+/// not have to be orderable itself.
+///
+/// Two versions with the same number compare `Equal` regardless of their
+/// contents. This is deliberately coarser than `==`, which also compares
+/// `obj`, so an ordered collection such as a `BTreeSet` should hold at
+/// most one value per version number, or it will treat them as one.
+impl<O: PartialEq> PartialOrd for Versioned<O> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.version.cmp(&other.version))
+    }
+}
+
+impl<O: Eq> Ord for Versioned<O> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.version.cmp(&other.version)
+    }
+}
+
+/// Wrapping a value with `.into()` starts its lineage at version 1, the
+/// same as [`version`].
+impl<O> From<O> for Versioned<O> {
+    fn from(obj: O) -> Self {
+        version(obj)
+    }
+}
+
+/// Borrows `obj` without moving it out, as in `example_1`.
+impl<O> AsRef<O> for Versioned<O> {
+    fn as_ref(&self) -> &O {
+        &self.obj
+    }
+}
+
+/// Lets a set of `Versioned` values be searched by the bare object, such
+/// as a `HashSet<Versioned<Config>>` queried with a `&Config`.
+///
+/// `Borrow` expects the two types to agree on equality, but two
+/// `Versioned` values holding the same object at different versions are
+/// not equal. Such a set should therefore hold at most one version of
+/// each object, or a lookup may find any of them.
+impl<O> Borrow<O> for Versioned<O> {
+    fn borrow(&self) -> &O {
+        &self.obj
+    }
+}
+
+/// Hashes only `obj`, so that the hash agrees with the [`Borrow`] impl.
+/// Equal `Versioned` values hold equal objects, so this is also
+/// consistent with `Eq`.
+impl<O: Hash> Hash for Versioned<O> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.obj.hash(state);
+    }
+}
+
+/// Formats as `v{version}: {obj}`.
+impl<O: fmt::Display> fmt::Display for Versioned<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}: {}", self.version, self.obj)
+    }
+}
+
+/// Field access and method calls fall through to `obj`, while
+/// `version` remains a real field on the wrapper itself.
+///
+/// The example sticks to `core` types so that it also runs without the
+/// `std` feature; `Versioned<Config>` works the same way.
+///
+/// ```
+/// use rust_patterns_for_lifetime_management::versioned::version;
+///
+/// struct Upstream {
+///     host: &'static str,
+///     port: u16,
+/// }
+///
+/// fn is_valid_upstream(upstream: &Upstream) -> bool {
+///     !upstream.host.is_empty() && upstream.port != 0
+/// }
+///
+/// let versioned_upstream = version(Upstream {
+///     host: "127.0.0.1",
+///     port: 8080,
+/// });
+///
+/// assert_eq!(versioned_upstream.version, 1);
+/// assert_eq!(versioned_upstream.host, "127.0.0.1");
+/// // `&Versioned<Upstream>` coerces to `&Upstream`.
+/// assert!(is_valid_upstream(&versioned_upstream));
+/// ```
+impl<O> Deref for Versioned<O> {
+    type Target = O;
+
+    fn deref(&self) -> &O {
+        &self.obj
+    }
+}
+
+impl<O> DerefMut for Versioned<O> {
+    fn deref_mut(&mut self) -> &mut O {
+        &mut self.obj
+    }
+}
+
+/// Takes ownership of `obj` and starts a new lineage at version 1.
+pub fn version<O>(obj: O) -> Versioned<O> {
+    Versioned::stamped(obj, 1)
+}
+
+/// `prev` is only borrowed to read its version number.
+pub fn next_version<O>(obj: O, prev: &Versioned<O>) -> Versioned<O> {
+    Versioned::stamped(obj, prev.version + 1)
+}
+
+/// Puts [`version`] and [`Versioned::bump`] on the type being versioned,
+/// so that `config.to_versioned()` reads the same for any struct. Both
+/// methods are provided, so `#[derive(Versionable)]` (with the `derive`
+/// feature) or an empty `impl` is all a type needs.
+pub trait Versionable: Sized {
+    /// Moves `self` into a new lineage at version 1.
+    fn to_versioned(self) -> Versioned<Self> {
+        version(self)
+    }
+
+    /// Records `versioned`'s current contents as its next version.
+    fn bump(versioned: &mut Versioned<Self>) {
+        versioned.bump();
+    }
+}
+
+#[cfg(feature = "derive")]
+pub use rust_patterns_for_lifetime_management_derive::Versionable;
+
+/// Reads a version number back from a tag such as `"v3"`, `"V3"` or
+/// plain `"3"`, as written in CLI arguments and logs. Nothing but ASCII
+/// digits may follow the optional prefix, so `"ver3"`, `"v"`, `""` and
+/// `"+3"` are all rejected.
+pub fn parse_version_tag(s: &str) -> Result<u32, ParseVersionError> {
+    let digits = s.strip_prefix(['v', 'V']).unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseVersionError::Invalid);
+    }
+
+    // Only overflow is left to fail on.
+    digits.parse().map_err(|_| ParseVersionError::TooLarge)
+}
+
+/// `prev` is only borrowed to read its version number; the new
+/// `Versioned` starts at 1 when there is no previous version.
+#[cfg(feature = "std")]
+#[deprecated(note = "use `version` or `next_version`, which work for any type")]
+pub fn save_config_version(config: Config, prev: Option<&Versioned<Config>>) -> Versioned<Config> {
+    match prev {
+        Some(prev) => next_version(config, prev),
+        None => version(config),
+    }
+}
+
+/// Like [`is_valid_config`], but version 0 is also rejected. Versions
+/// handed out by [`version`] and [`next_version`] start at 1, so a zero
+/// means the wrapper was built by hand.
+#[cfg(feature = "std")]
+pub fn is_valid_versioned(v: &Versioned<Config>) -> bool {
+    v.version >= 1 && is_valid_config(v)
+}
+
+/// Owns every version pushed into it, so callers no longer have to
+/// thread the previous `Versioned` through by hand.
+///
+/// Versions are kept in ascending order of version number, which lets
+/// lookups binary search rather than scan.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct VersionHistory<O> {
+    versions: VecDeque<Versioned<O>>,
+    last_version: u32,
+    max: Option<usize>,
+    /// For each squashed entry, the first version it replaced.
+    squashed_from: HashMap<u32, u32>,
+}
+
+#[cfg(feature = "std")]
+impl<O> VersionHistory<O> {
+    pub fn new() -> Self {
+        VersionHistory {
+            versions: VecDeque::new(),
+            last_version: 0,
+            max: None,
+            squashed_from: HashMap::new(),
+        }
+    }
+
+    /// A history that retains at most `max` versions, dropping the oldest
+    /// once it is full. Version numbers keep counting up regardless.
+    pub fn with_capacity(max: usize) -> Self {
+        VersionHistory {
+            max: Some(max),
+            ..VersionHistory::new()
+        }
+    }
+
+    /// Takes ownership of `obj` and stores it as the next version.
+    /// The first push is always version 1.
+    pub fn push(&mut self, obj: O) {
+        self.last_version += 1;
+        self.versions
+            .push_back(Versioned::stamped(obj, self.last_version));
+
+        if let Some(max) = self.max {
+            self.evict_to(max);
+        }
+    }
+
+    /// Drops all but the latest `n` versions, once. Unlike a history built
+    /// with [`VersionHistory::with_capacity`], later pushes are not
+    /// bounded. The retained versions keep their numbers.
+    pub fn retain_latest(&mut self, n: usize) {
+        self.evict_to(n);
+    }
+
+    fn evict_to(&mut self, len: usize) {
+        while self.versions.len() > len {
+            if let Some(evicted) = self.versions.pop_front() {
+                self.squashed_from.remove(&evicted.version);
+            }
+        }
+    }
+
+    pub fn latest(&self) -> Option<&Versioned<O>> {
+        self.versions.back()
+    }
+
+    pub fn get(&self, version: u32) -> Option<&Versioned<O>> {
+        self.position(version).map(|i| &self.versions[i])
+    }
+
+    fn position(&self, version: u32) -> Option<usize> {
+        self.versions
+            .binary_search_by_key(&version, |v| v.version)
+            .ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// Pairs up the versions the two histories have in common, by version
+    /// number and in ascending order. Versions in only one of them are
+    /// skipped. Both histories are only borrowed.
+    ///
+    /// Both are already in version order, so this walks each of them once.
+    pub fn zip<'a, B>(
+        &'a self,
+        other: &'a VersionHistory<B>,
+    ) -> impl Iterator<Item = (&'a Versioned<O>, &'a Versioned<B>)> {
+        let mut ours = self.versions.iter().peekable();
+        let mut theirs = other.versions.iter().peekable();
+
+        iter::from_fn(move || loop {
+            match ours.peek()?.version.cmp(&theirs.peek()?.version) {
+                Ordering::Less => {
+                    ours.next();
+                }
+                Ordering::Greater => {
+                    theirs.next();
+                }
+                Ordering::Equal => return ours.next().zip(theirs.next()),
+            }
+        })
+    }
+
+    /// Collapses versions `from..=to` into the single entry for `to`,
+    /// dropping the others. Only `to`'s content is kept, and it keeps its
+    /// version number; [`VersionHistory::squashed_from`] records where the
+    /// range started. Both ends must still be in the history.
+    pub fn squash(&mut self, from: u32, to: u32) -> Result<(), SquashError> {
+        if from > to {
+            return Err(SquashError::InvalidRange { from, to });
+        }
+        for version in [from, to] {
+            if self.get(version).is_none() {
+                return Err(SquashError::NotFound(version));
+            }
+        }
+
+        self.versions
+            .retain(|v| v.version < from || v.version >= to);
+
+        // If `from` was itself a squash, the new range starts where that
+        // one did.
+        let start = self.squashed_from(from).unwrap_or(from);
+        self.squashed_from
+            .retain(|&version, _| version < from || version > to);
+        if start < to {
+            self.squashed_from.insert(to, start);
+        }
+        Ok(())
+    }
+
+    /// The first version that `version` absorbed in a
+    /// [`VersionHistory::squash`], or `None` if it was never squashed.
+    pub fn squashed_from(&self, version: u32) -> Option<u32> {
+        self.squashed_from.get(&version).copied()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<O: Clone> VersionHistory<O> {
+    /// Restores the content of `version` by pushing a clone of it as the
+    /// new latest version. Nothing is truncated, so the rollback itself
+    /// becomes part of the history.
+    pub fn rollback(&mut self, version: u32) -> Result<(), RollbackError> {
+        let obj = self
+            .get(version)
+            .ok_or(RollbackError::NotFound(version))?
+            .obj
+            .clone();
+
+        self.push(obj);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl VersionHistory<Config> {
+    /// Pushes `config` only if its checksum differs from the latest
+    /// version's, returning whether a new version was recorded. A skipped
+    /// `config` is simply dropped.
+    pub fn push_if_changed(&mut self, config: Config) -> bool {
+        let unchanged = self
+            .latest()
+            .is_some_and(|latest| latest.checksum() == config.checksum());
+
+        if !unchanged {
+            self.push(config);
+        }
+        !unchanged
+    }
+}
+
+/// Each item is pushed in iteration order, so a fresh history collects
+/// into versions `1..=n`.
+#[cfg(feature = "std")]
+impl<O> FromIterator<O> for VersionHistory<O> {
+    fn from_iter<I: IntoIterator<Item = O>>(iter: I) -> Self {
+        let mut history = VersionHistory::new();
+        history.extend(iter);
+        history
+    }
+}
+
+/// Pushes each item in turn, continuing from the latest version.
+#[cfg(feature = "std")]
+impl<O> Extend<O> for VersionHistory<O> {
+    fn extend<I: IntoIterator<Item = O>>(&mut self, iter: I) {
+        for obj in iter {
+            self.push(obj);
+        }
+    }
+}
+
+/// `history[version]` looks up by version number, not position.
+///
+/// Through `IndexMut`, `obj` may be edited freely, but changing `version`
+/// would break the ordering that lookups rely on.
+///
+/// # Panics
+///
+/// If `version` is not in the history; see [`VersionHistory::get`] for
+/// the non-panicking form.
+#[cfg(feature = "std")]
+impl<O> Index<u32> for VersionHistory<O> {
+    type Output = Versioned<O>;
+
+    fn index(&self, version: u32) -> &Versioned<O> {
+        self.get(version)
+            .unwrap_or_else(|| panic!("version {version} is not in the history"))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<O> IndexMut<u32> for VersionHistory<O> {
+    fn index_mut(&mut self, version: u32) -> &mut Versioned<O> {
+        let i = self
+            .position(version)
+            .unwrap_or_else(|| panic!("version {version} is not in the history"));
+        &mut self.versions[i]
+    }
+}
+
+#[cfg(feature = "serde")]
+impl VersionHistory<Config> {
+    /// Writes every retained version to `path` as one JSON array, in the
+    /// same crash-safe way as [`Versioned::checkpoint`]. Squash markers and
+    /// the capacity limit are not saved.
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        let json = serde_json::to_vec(&self.versions).map_err(ConfigError::InvalidJson)?;
+        write_atomically(path, &json)
+    }
+
+    /// Reads back a history written by [`VersionHistory::save`], with
+    /// every version number exactly as saved. New pushes continue from the
+    /// highest of them.
+    pub fn load(path: &Path) -> Result<VersionHistory<Config>, ConfigError> {
+        let json = read_file(path)?;
+        let mut versions: VecDeque<Versioned<Config>> =
+            serde_json::from_slice(&json).map_err(ConfigError::InvalidJson)?;
+        // A hand-edited file may list versions in any order.
+        versions.make_contiguous().sort_by_key(|v| v.version);
+
+        Ok(VersionHistory {
+            last_version: versions.iter().map(|v| v.version).max().unwrap_or(0),
+            versions,
+            ..VersionHistory::new()
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<O> Default for VersionHistory<O> {
+    fn default() -> Self {
+        VersionHistory::new()
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq, Eq)]
+pub enum RollbackError {
+    NotFound(u32),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for RollbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RollbackError::NotFound(version) => write!(f, "version {version} does not exist"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for RollbackError {}
+
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq, Eq)]
+pub enum SquashError {
+    /// `from` is after `to`.
+    InvalidRange {
+        from: u32,
+        to: u32,
+    },
+    NotFound(u32),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for SquashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SquashError::InvalidRange { from, to } => {
+                write!(f, "cannot squash versions {from} to {to}")
+            }
+            SquashError::NotFound(version) => write!(f, "version {version} does not exist"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for SquashError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseVersionError {
+    /// The tag is not an optional `v` or `V` followed by digits.
+    Invalid,
+    /// The number does not fit in a `u32`.
+    TooLarge,
+}
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseVersionError::Invalid => write!(f, "version tag must look like `v3` or `3`"),
+            ParseVersionError::TooLarge => write!(f, "version number is too large"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseVersionError {}
@@ -0,0 +1,104 @@
+use std::fmt;
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::Versioned;
+
+/// A [`VersionHistory`](super::VersionHistory) for values that are too
+/// large to keep many live copies of. Only the latest version is held as
+/// a `Versioned<O>`; every older one is serialized to JSON and deflated.
+///
+/// An archived version no longer exists as an `O`, so [`get`] decompresses
+/// it into a brand new owned value instead of lending one out.
+///
+/// [`get`]: CompressedHistory::get
+pub struct CompressedHistory<O> {
+    archived: Vec<(u32, Vec<u8>)>,
+    latest: Option<Versioned<O>>,
+    last_version: u32,
+}
+
+impl<O: Serialize + DeserializeOwned + Clone> CompressedHistory<O> {
+    pub fn new() -> Self {
+        CompressedHistory {
+            archived: Vec::new(),
+            latest: None,
+            last_version: 0,
+        }
+    }
+
+    /// Takes ownership of `obj` as the next version and compresses the
+    /// version it displaces. If that version cannot be serialized the
+    /// history is left untouched and `obj` is dropped.
+    pub fn push(&mut self, obj: O) -> Result<(), serde_json::Error> {
+        if let Some(prev) = &self.latest {
+            let blob = compress(prev)?;
+            self.archived.push((prev.version, blob));
+        }
+
+        self.last_version += 1;
+        self.latest = Some(Versioned::stamped(obj, self.last_version));
+        Ok(())
+    }
+
+    /// Borrows the live latest version without decompressing anything.
+    pub fn latest(&self) -> Option<&Versioned<O>> {
+        self.latest.as_ref()
+    }
+
+    /// Returns an owned copy of `version`: a clone for the latest, or a
+    /// freshly decompressed value for an archived one. `Ok(None)` means no
+    /// such version was kept; an error means its blob could not be
+    /// decompressed or deserialized.
+    pub fn get(&self, version: u32) -> Result<Option<Versioned<O>>, serde_json::Error> {
+        match &self.latest {
+            Some(latest) if latest.version == version => return Ok(Some(latest.clone())),
+            _ => {}
+        }
+
+        let Ok(index) = self
+            .archived
+            .binary_search_by_key(&version, |(version, _)| *version)
+        else {
+            return Ok(None);
+        };
+        let decoder = DeflateDecoder::new(&self.archived[index].1[..]);
+        serde_json::from_reader(decoder).map(Some)
+    }
+
+    pub fn len(&self) -> usize {
+        self.archived.len() + usize::from(self.latest.is_some())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.latest.is_none()
+    }
+}
+
+/// Shows how many versions are archived rather than their compressed
+/// bytes.
+impl<O: fmt::Debug> fmt::Debug for CompressedHistory<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressedHistory")
+            .field("archived", &self.archived.len())
+            .field("latest", &self.latest)
+            .field("last_version", &self.last_version)
+            .finish()
+    }
+}
+
+impl<O: Serialize + DeserializeOwned + Clone> Default for CompressedHistory<O> {
+    fn default() -> Self {
+        CompressedHistory::new()
+    }
+}
+
+fn compress<O: Serialize>(versioned: &Versioned<O>) -> Result<Vec<u8>, serde_json::Error> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, versioned)?;
+    encoder.finish().map_err(serde_json::Error::io)
+}
@@ -0,0 +1,502 @@
+use std::future::Future;
+use std::panic;
+use std::slice;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
+use std::time::Duration;
+use std::vec;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use reqwest::{Client, Error, Response};
+use tokio::sync::watch;
+use tokio::task::{self, JoinSet};
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::{is_valid_config, Config};
+
+pub mod rolling;
+
+/// Each worker holds a counted reference to the same `RwLock<Config>`,
+/// so an edit made through any handle is seen by all of them. Reads go
+/// through `config.read()`.
+///
+/// `task` is arbitrary per-worker state that is owned by the worker
+/// alone, unlike the shared config.
+///
+/// The run counter sits behind its own `Arc`, so clones of a worker moved
+/// into spawned tasks all count towards the same total.
+///
+/// `id` is assigned by the owning [`WorkerPool`], which never reuses one.
+/// A worker built on its own is given its id by the caller, who is then
+/// responsible for keeping it apart from any pool's.
+/// A pool built with metrics disabled gives its workers no counter at
+/// all, and their runs always read 0.
+#[derive(Clone, Debug)]
+pub struct Worker<T = ()> {
+    pub config: Arc<RwLock<Config>>,
+    pub task: T,
+    id: usize,
+    client: Client,
+    runs: Option<Arc<AtomicU64>>,
+}
+
+impl<T> Worker<T> {
+    pub fn new(id: usize, config: Arc<RwLock<Config>>, task: T) -> Worker<T> {
+        Worker::with_client(id, config, task, Client::new())
+    }
+
+    /// Like [`Worker::new`], but `run` goes through `client` rather than a
+    /// new one. `Client` is itself a handle to a connection pool, so
+    /// workers given clones of one client share its connections.
+    pub fn with_client(
+        id: usize,
+        config: Arc<RwLock<Config>>,
+        task: T,
+        client: Client,
+    ) -> Worker<T> {
+        Worker {
+            config,
+            task,
+            id,
+            client,
+            runs: Some(Arc::new(AtomicU64::new(0))),
+        }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// How many times this worker, or any clone of it, has been run.
+    pub fn runs(&self) -> u64 {
+        self.runs
+            .as_ref()
+            .map_or(0, |runs| runs.load(Ordering::Relaxed))
+    }
+
+    fn record_run(&self) {
+        if let Some(runs) = &self.runs {
+            runs.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// A non-owning reference to the shared config. It stops upgrading
+    /// once the pool and every worker holding the config have been
+    /// dropped.
+    pub fn config_weak(&self) -> Weak<RwLock<Config>> {
+        Arc::downgrade(&self.config)
+    }
+
+    /// The same GET as `example_4`, but through the client this worker
+    /// owns rather than one created on the spot.
+    pub async fn run(&self, url: &str) -> Result<Response, Error> {
+        self.record_run();
+        self.client.get(url).send().await
+    }
+
+    /// Like [`Worker::run`], but retries up to `retries` times, waiting
+    /// `base_delay`, then twice that, and so on between attempts.
+    ///
+    /// Only failures that may be transient are retried: timeouts,
+    /// connection errors and `5xx` responses. Anything else, such as an
+    /// invalid URL, is returned straight away. Once the retries run out,
+    /// the last response or error is returned as is. Every attempt counts
+    /// as a run.
+    pub async fn run_with_retry(
+        &self,
+        url: &str,
+        retries: usize,
+        base_delay: Duration,
+    ) -> Result<Response, Error> {
+        let mut delay = base_delay;
+        for _ in 0..retries {
+            match self.run(url).await {
+                Ok(response) if response.status().is_server_error() => {}
+                Err(err) if err.is_timeout() || err.is_connect() => {}
+                done => return done,
+            }
+            time::sleep(delay).await;
+            delay = delay.saturating_mul(2);
+        }
+        self.run(url).await
+    }
+}
+
+/// Owns a set of workers that all share a single heap allocation of
+/// their `Config`, as in `example_5`.
+#[derive(Debug)]
+pub struct WorkerPool {
+    config: Arc<RwLock<Config>>,
+    client: Client,
+    workers: Vec<Worker>,
+    next_id: usize,
+    metrics: bool,
+    shutdown: CancellationToken,
+    reloads: watch::Sender<Arc<Config>>,
+}
+
+impl WorkerPool {
+    /// Moves `config` into an `Arc` exactly once and hands each of the
+    /// `size` workers a counted reference to it. A single `Client` is
+    /// created up front and a handle to it is moved into every worker, so
+    /// they all share one connection pool.
+    ///
+    /// The [`subscribe`](WorkerPool::subscribe) channel starts out with a
+    /// clone of `config`, made once for the whole pool.
+    pub fn new(config: Config, size: usize) -> WorkerPool {
+        WorkerPool::with_options(config, size, true)
+    }
+
+    /// Like [`WorkerPool::new`], for a config that is already in an
+    /// `Arc`. The `RwLock` has to own the config it guards, so the config
+    /// is moved out of `config`, which must be its only handle.
+    ///
+    /// If other handles are still alive, `config` is handed back untouched
+    /// rather than silently deep-cloned, since the pool could not share
+    /// the config with them anyway.
+    pub fn from_arc(config: Arc<Config>, size: usize) -> Result<WorkerPool, Arc<Config>> {
+        let config = Arc::try_unwrap(config)?;
+        Ok(WorkerPool::with_options(config, size, true))
+    }
+
+    pub fn builder() -> WorkerPoolBuilder {
+        WorkerPoolBuilder::new()
+    }
+
+    fn with_options(config: Config, size: usize, metrics: bool) -> WorkerPool {
+        let mut pool = WorkerPool {
+            reloads: watch::channel(Arc::new(config.clone())).0,
+            config: Arc::new(RwLock::new(config)),
+            client: Client::new(),
+            workers: Vec::with_capacity(size),
+            next_id: 0,
+            metrics,
+            shutdown: CancellationToken::new(),
+        };
+
+        pool.resize(size);
+        pool
+    }
+
+    /// Grows the pool with workers that share the existing config and
+    /// client, or drops workers from the end to shrink it. The config
+    /// itself is never reallocated either way.
+    ///
+    /// Surviving workers keep their ids, and new workers always get fresh
+    /// ones, even after a shrink.
+    pub fn resize(&mut self, new_size: usize) {
+        if new_size <= self.workers.len() {
+            self.workers.truncate(new_size);
+            return;
+        }
+
+        let first_id = self.next_id;
+        let added = new_size - self.workers.len();
+        self.next_id += added;
+
+        let new_workers = (first_id..self.next_id).map(|id| Worker {
+            config: Arc::clone(&self.config),
+            task: (),
+            id,
+            client: self.client.clone(),
+            runs: self.metrics.then(|| Arc::new(AtomicU64::new(0))),
+        });
+        self.workers.extend(new_workers);
+    }
+
+    pub fn workers(&self) -> &[Worker] {
+        &self.workers
+    }
+
+    /// Looks a worker up by the id it was given, rather than by its
+    /// current position in the pool.
+    pub fn get(&self, id: usize) -> Option<&Worker> {
+        self.workers.iter().find(|worker| worker.id == id)
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, Worker> {
+        self.workers.iter()
+    }
+
+    /// Spawns one tokio task per worker and waits for all of them.
+    ///
+    /// Each task is given its own clone of a worker, so it owns everything
+    /// it needs and can outlive this borrow of the pool, as in
+    /// `example_4`. Outputs are returned in worker order.
+    ///
+    /// Each task also receives a child of the pool's cancellation token,
+    /// which is cancelled by [`WorkerPool::shutdown`]. Observing it is up
+    /// to the task. After a shutdown, every task starts out cancelled.
+    pub async fn spawn_all<F, Fut>(&self, f: F) -> Vec<Fut::Output>
+    where
+        F: Fn(Worker, CancellationToken) -> Fut,
+        Fut: Future + Send + 'static,
+        Fut::Output: Send + 'static,
+    {
+        // Every task is spawned before any is awaited, so they all run
+        // concurrently.
+        let handles: Vec<_> = self
+            .workers
+            .iter()
+            .cloned()
+            .map(|worker| {
+                worker.record_run();
+                tokio::spawn(f(worker, self.shutdown.child_token()))
+            })
+            .collect();
+
+        let mut outputs = Vec::with_capacity(handles.len());
+        for handle in handles {
+            match handle.await {
+                Ok(output) => outputs.push(output),
+                Err(err) => panic::resume_unwind(err.into_panic()),
+            }
+        }
+        outputs
+    }
+
+    /// Like [`WorkerPool::spawn_all`], but for synchronous work that would
+    /// block the async runtime: each call to `f` runs on tokio's blocking
+    /// thread pool via `spawn_blocking`.
+    ///
+    /// Every thread gets its own clone of both `f` and a worker, so
+    /// nothing is borrowed from the pool. Outputs are returned in worker
+    /// order.
+    pub async fn spawn_blocking_all<T: Send + 'static>(
+        &self,
+        f: impl Fn(Worker) -> T + Send + Sync + Clone + 'static,
+    ) -> Vec<T> {
+        let handles: Vec<_> = self
+            .workers
+            .iter()
+            .cloned()
+            .map(|worker| {
+                worker.record_run();
+                let f = f.clone();
+                task::spawn_blocking(move || f(worker))
+            })
+            .collect();
+
+        let mut outputs = Vec::with_capacity(handles.len());
+        for handle in handles {
+            match handle.await {
+                Ok(output) => outputs.push(output),
+                Err(err) => panic::resume_unwind(err.into_panic()),
+            }
+        }
+        outputs
+    }
+
+    /// Runs `map` on every worker concurrently and folds the outputs into
+    /// `init` with `reduce`, in the order the tasks complete rather than
+    /// worker order. As with `spawn_all`, each task owns its worker.
+    pub async fn map_reduce<T, Fut, R>(
+        &self,
+        map: impl Fn(Worker) -> Fut,
+        init: R,
+        reduce: impl Fn(R, T) -> R,
+    ) -> R
+    where
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut tasks = JoinSet::new();
+        for worker in self.workers.iter().cloned() {
+            worker.record_run();
+            tasks.spawn(map(worker));
+        }
+
+        let mut acc = init;
+        while let Some(output) = tasks.join_next().await {
+            match output {
+                Ok(output) => acc = reduce(acc, output),
+                Err(err) => panic::resume_unwind(err.into_panic()),
+            }
+        }
+        acc
+    }
+
+    /// Consumes the pool and hands its workers back as a `Vec`, for
+    /// teardown that needs each worker's own state. Dropping the pool
+    /// releases only its own handle to the config; the config itself
+    /// stays alive for as long as any returned worker holds one.
+    pub fn drain(self) -> Vec<Worker> {
+        self.workers
+    }
+
+    /// Consumes the pool and freezes its workers into a shared slice.
+    /// Cloning the result only bumps a reference count, as with the config
+    /// in `example_5`, so the whole set can be handed to several owners.
+    pub fn into_shared(self) -> Arc<[Worker]> {
+        self.workers.into()
+    }
+
+    /// Runs [`is_valid_config`] against the config each worker currently
+    /// sees, returning the indices of the workers that fail. Today every
+    /// worker shares one config, so they all pass or all fail together.
+    pub fn health_check(&self) -> Result<(), Vec<usize>> {
+        let failing: Vec<usize> = self
+            .workers
+            .iter()
+            .enumerate()
+            .filter(|(_, worker)| {
+                let config = worker.config.read().unwrap_or_else(PoisonError::into_inner);
+                !is_valid_config(&config)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        if failing.is_empty() {
+            Ok(())
+        } else {
+            Err(failing)
+        }
+    }
+
+    /// Runs `f` on every worker in parallel on rayon's thread pool, for
+    /// CPU-bound work that has no need for tokio. `f` only borrows each
+    /// worker, and outputs are returned in worker order.
+    #[cfg(feature = "rayon")]
+    pub fn par_map<T: Send>(&self, f: impl Fn(&Worker) -> T + Sync) -> Vec<T> {
+        // Only `&f` is shared with the pool threads, which `Sync` allows.
+        self.workers.par_iter().map(&f).collect()
+    }
+
+    /// The sum of every worker's run counter.
+    pub fn total_runs(&self) -> u64 {
+        self.workers.iter().map(Worker::runs).sum()
+    }
+
+    /// Cancels the token handed to every task started by `spawn_all`,
+    /// including tasks that are already in flight.
+    ///
+    /// Shutdown is terminal: the token stays cancelled, so tasks spawned
+    /// by any later `spawn_all` are cancelled from the start. Build a new
+    /// pool to start over.
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+
+    /// How many handles to the shared config are alive: one for the pool
+    /// itself plus one for every worker, including clones handed out.
+    pub fn config_refcount(&self) -> usize {
+        Arc::strong_count(&self.config)
+    }
+
+    /// Replaces the config seen by every worker at once, waiting for any
+    /// outstanding [`config_read`](WorkerPool::config_read) or
+    /// [`config_mut`](WorkerPool::config_mut) guard to be released first.
+    /// The old config is dropped in place.
+    ///
+    /// Every receiver handed out by [`WorkerPool::subscribe`] is then sent
+    /// `new` itself. The lock and the channel each need a config of their
+    /// own, so `new` is cloned once here.
+    pub fn reload(&self, new: Config) {
+        *self.config_mut() = new.clone();
+        self.reloads.send_replace(Arc::new(new));
+    }
+
+    /// Shared access to the config every worker sees. Writers wait until
+    /// the guard is dropped.
+    ///
+    /// As with `SharedConfig`, a poisoned lock is recovered rather than
+    /// propagated.
+    pub fn config_read(&self) -> RwLockReadGuard<'_, Config> {
+        self.config.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Exclusive access to the config every worker sees, edited in place
+    /// without copying it. Workers block on their next read until the
+    /// guard is dropped, and then see all of the edits at once.
+    ///
+    /// Edits made through the guard are not announced to
+    /// [`WorkerPool::subscribe`]; only `reload` is.
+    pub fn config_mut(&self) -> RwLockWriteGuard<'_, Config> {
+        self.config.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// A receiver of every config passed to [`WorkerPool::reload`], for
+    /// async tasks that want to react to changes rather than poll. It
+    /// starts out holding the config the pool was built with.
+    ///
+    /// `borrow()` returns exactly the config that was announced, even if
+    /// the shared one has since been edited through `config_mut`.
+    ///
+    /// The pool owns the only sender, so once the pool is dropped
+    /// `changed()` returns an error and tasks can exit.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<Config>> {
+        self.reloads.subscribe()
+    }
+}
+
+/// Collects the options for a [`WorkerPool`] before any worker is
+/// created. As with `ConfigBuilder`, each method takes the builder by
+/// value and hands it back.
+///
+/// Without a `config`, the pool is built with `Config::default()`; with
+/// no `size`, it has no workers until it is resized. Metrics are enabled
+/// unless turned off.
+#[derive(Debug)]
+pub struct WorkerPoolBuilder {
+    config: Config,
+    size: usize,
+    metrics: bool,
+}
+
+impl WorkerPoolBuilder {
+    pub fn new() -> Self {
+        WorkerPoolBuilder {
+            config: Config::default(),
+            size: 0,
+            metrics: true,
+        }
+    }
+
+    /// Takes ownership of the config the pool will share.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Whether workers count their runs. See [`Worker::runs`].
+    pub fn with_metrics(mut self, metrics: bool) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn build(self) -> WorkerPool {
+        WorkerPool::with_options(self.config, self.size, self.metrics)
+    }
+}
+
+impl Default for WorkerPoolBuilder {
+    fn default() -> Self {
+        WorkerPoolBuilder::new()
+    }
+}
+
+/// Consumes the pool and moves each worker out to the caller.
+impl IntoIterator for WorkerPool {
+    type Item = Worker;
+    type IntoIter = vec::IntoIter<Worker>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.workers.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a WorkerPool {
+    type Item = &'a Worker;
+    type IntoIter = slice::Iter<'a, Worker>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,86 @@
+use std::slice;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::config::Config;
+
+/// A worker with a config cell of its own, rather than the lock shared by
+/// every [`Worker`](crate::worker::Worker) in a pool. Reads go through
+/// `config.load()`.
+///
+/// Clones of a worker share its cell, so a clone moved into a task still
+/// sees that worker's reloads.
+#[derive(Clone, Debug)]
+pub struct RollingWorker {
+    pub config: Arc<ArcSwap<Config>>,
+    id: usize,
+}
+
+impl RollingWorker {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+/// A pool that can move its workers onto a new config a few at a time,
+/// so that a bad config can be noticed before it reaches every worker.
+///
+/// The cells start out pointing at one shared `Arc<Config>`, and each
+/// reload shares one new `Arc` between the cells it updates, so the config
+/// is never copied per worker.
+#[derive(Debug)]
+pub struct RollingPool {
+    workers: Vec<RollingWorker>,
+}
+
+impl RollingPool {
+    /// Moves `config` into an `Arc` once and gives each of the `size`
+    /// workers its own cell pointing at it. Ids count up from 0.
+    pub fn new(config: Config, size: usize) -> RollingPool {
+        let config = config.into_arc();
+        let workers = (0..size)
+            .map(|id| RollingWorker {
+                config: Arc::new(ArcSwap::new(Arc::clone(&config))),
+                id,
+            })
+            .collect();
+
+        RollingPool { workers }
+    }
+
+    pub fn workers(&self) -> &[RollingWorker] {
+        &self.workers
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, RollingWorker> {
+        self.workers.iter()
+    }
+
+    /// Stores `new` into the workers' cells `batch` at a time, in worker
+    /// order. Each store is atomic, and workers not yet reached keep
+    /// serving the old config.
+    ///
+    /// `pause` runs between batches with the number of workers updated so
+    /// far. It is where the caller waits, checks health, or otherwise
+    /// decides when the next batch may go; it is not called after the
+    /// last batch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch` is 0, like [`slice::chunks`].
+    pub fn rolling_reload(&self, new: Config, batch: usize, mut pause: impl FnMut(usize)) {
+        let new = new.into_arc();
+        let mut updated = 0;
+
+        for (i, workers) in self.workers.chunks(batch).enumerate() {
+            if i > 0 {
+                pause(updated);
+            }
+            for worker in workers {
+                worker.config.store(Arc::clone(&new));
+            }
+            updated += workers.len();
+        }
+    }
+}